@@ -0,0 +1,88 @@
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ActionGroup {
+	GameState,
+	Gameplay,
+	Camera,
+	/* Keys in keyconfig.toml that are not in ACTIONS */
+	Other,
+}
+
+impl ActionGroup {
+	pub const ALL: [ActionGroup; 4] = [
+		ActionGroup::GameState,
+		ActionGroup::Gameplay,
+		ActionGroup::Camera,
+		ActionGroup::Other,
+	];
+
+	pub fn title(self) -> &'static str {
+		match self {
+			ActionGroup::GameState => "Change Game State",
+			ActionGroup::Gameplay => "Gameplay",
+			ActionGroup::Camera => "Unlocked camera",
+			ActionGroup::Other => "Other",
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Action {
+	/* Name in keyconfig.toml */
+	pub key: &'static str,
+	pub group: ActionGroup,
+	pub name: &'static str,
+}
+
+const fn action(key: &'static str, group: ActionGroup, name: &'static str) -> Action {
+	Action { key, group, name }
+}
+
+/* Every action the DLL reads from keyconfig.toml, new ones only need a line here */
+pub const ACTIONS: &[Action] = &[
+	action("TEST", ActionGroup::GameState, "Test"),
+	action("SERVICE", ActionGroup::GameState, "Service"),
+	action("ADVERTISE", ActionGroup::GameState, "Advertise"),
+	action("GAME", ActionGroup::GameState, "Game"),
+	action("DATA_TEST", ActionGroup::GameState, "Data test"),
+	action("TEST_MODE", ActionGroup::GameState, "Test mode"),
+	action("APP_ERROR", ActionGroup::GameState, "App error"),
+	action("START", ActionGroup::Gameplay, "Start"),
+	action("TRIANGLE", ActionGroup::Gameplay, "Triangle"),
+	action("SQUARE", ActionGroup::Gameplay, "Square"),
+	action("CROSS", ActionGroup::Gameplay, "Cross"),
+	action("CIRCLE", ActionGroup::Gameplay, "Circle"),
+	action("LEFT_LEFT", ActionGroup::Gameplay, "Left slider left"),
+	action("LEFT_RIGHT", ActionGroup::Gameplay, "Left slider right"),
+	action("RIGHT_LEFT", ActionGroup::Gameplay, "Right slider left"),
+	action("RIGHT_RIGHT", ActionGroup::Gameplay, "Right slider right"),
+	action("CAMERA_UNLOCK_TOGGLE", ActionGroup::Camera, "Unlock camera"),
+	action("CAMERA_MOVE_FORWARD", ActionGroup::Camera, "Move forward"),
+	action("CAMERA_MOVE_BACKWARD", ActionGroup::Camera, "Move backward"),
+	action("CAMERA_MOVE_LEFT", ActionGroup::Camera, "Move left"),
+	action("CAMERA_MOVE_RIGHT", ActionGroup::Camera, "Move right"),
+	action("CAMERA_MOVE_UP", ActionGroup::Camera, "Move up"),
+	action("CAMERA_MOVE_DOWN", ActionGroup::Camera, "Move down"),
+	action("CAMERA_ROTATE_CW", ActionGroup::Camera, "Rotate clockwise"),
+	action(
+		"CAMERA_ROTATE_CCW",
+		ActionGroup::Camera,
+		"Rotate counterclockwise",
+	),
+	action("CAMERA_ZOOM_IN", ActionGroup::Camera, "Zoom in"),
+	action("CAMERA_ZOOM_OUT", ActionGroup::Camera, "Zoom out"),
+	action("CAMERA_MOVE_FAST", ActionGroup::Camera, "Move faster"),
+	action("CAMERA_MOVE_SLOW", ActionGroup::Camera, "Move slower"),
+];
+
+pub fn find_action(key: &str) -> Option<&'static Action> {
+	ACTIONS.iter().find(|action| action.key == key)
+}
+
+pub fn group_of(key: &str) -> ActionGroup {
+	find_action(key).map_or(ActionGroup::Other, |action| action.group)
+}
+
+/* Unknown keys are shown as they are written in the file */
+pub fn display_name(key: &str) -> &str {
+	find_action(key).map_or(key, |action| action.name)
+}
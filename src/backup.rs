@@ -0,0 +1,200 @@
+use std::{
+	io::Write,
+	path::{Path, PathBuf},
+	time::SystemTime,
+};
+
+pub const BACKUP_DIR: &str = "backups";
+pub const BACKUP_COUNT: usize = 5;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Backup {
+	/* 1 is the most recent */
+	pub index: usize,
+	pub path: PathBuf,
+	pub modified: Option<SystemTime>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DiffLine {
+	Same(String),
+	Removed(String),
+	Added(String),
+}
+
+fn backup_path(dir: &Path, file: &str, index: usize) -> PathBuf {
+	dir.join(BACKUP_DIR).join(format!("{}.{}.bak", file, index))
+}
+
+/* Keeps the previous contents in backups/ then replaces the file in one rename,
+ * so a crash leaves either the old or the new file but never half of one */
+pub fn write_atomic(dir: &Path, file: &str, contents: &str) -> std::io::Result<()> {
+	let path = dir.join(file);
+	let file_name = path
+		.file_name()
+		.map(|name| name.to_string_lossy().to_string())
+		.unwrap_or_default();
+	let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+	let mut temp = std::fs::File::create(&temp_path)?;
+	temp.write_all(contents.as_bytes())?;
+	temp.sync_all()?;
+	drop(temp);
+
+	if path.exists() {
+		if let Err(err) = rotate_backups(dir, file, &path) {
+			let _ = std::fs::remove_file(&temp_path);
+			return Err(err);
+		}
+	}
+	if let Err(err) = std::fs::rename(&temp_path, &path) {
+		let _ = std::fs::remove_file(&temp_path);
+		return Err(err);
+	}
+	#[cfg(unix)]
+	if let Some(parent) = path.parent() {
+		if let Ok(parent) = std::fs::File::open(parent) {
+			let _ = parent.sync_all();
+		}
+	}
+	Ok(())
+}
+
+fn rotate_backups(dir: &Path, file: &str, path: &Path) -> std::io::Result<()> {
+	let newest = backup_path(dir, file, 1);
+	if let Some(parent) = newest.parent() {
+		std::fs::create_dir_all(parent)?;
+	}
+	let oldest = backup_path(dir, file, BACKUP_COUNT);
+	if oldest.exists() {
+		std::fs::remove_file(oldest)?;
+	}
+	for index in (1..BACKUP_COUNT).rev() {
+		let from = backup_path(dir, file, index);
+		if from.exists() {
+			std::fs::rename(from, backup_path(dir, file, index + 1))?;
+		}
+	}
+	std::fs::copy(path, newest)?;
+	Ok(())
+}
+
+/* The removed file is kept as the newest backup */
+pub fn remove_file(dir: &Path, file: &str) -> std::io::Result<()> {
+	let path = dir.join(file);
+	rotate_backups(dir, file, &path)?;
+	std::fs::remove_file(path)
+}
+
+/* Never replaces an existing file */
+pub fn rename_file(dir: &Path, from: &str, to: &str) -> std::io::Result<()> {
+	let to = dir.join(to);
+	if to.exists() {
+		return Err(std::io::Error::new(
+			std::io::ErrorKind::AlreadyExists,
+			format!("{} already exists", to.display()),
+		));
+	}
+	std::fs::rename(dir.join(from), to)
+}
+
+pub fn backups(dir: &Path, file: &str) -> Vec<Backup> {
+	(1..=BACKUP_COUNT)
+		.map(|index| (index, backup_path(dir, file, index)))
+		.filter(|(_, path)| path.is_file())
+		.map(|(index, path)| Backup {
+			index,
+			modified: std::fs::metadata(&path)
+				.and_then(|metadata| metadata.modified())
+				.ok(),
+			path,
+		})
+		.collect()
+}
+
+/* Every file with at least one backup, including ones that have since been deleted */
+pub fn backed_up_files(dir: &Path) -> Vec<String> {
+	let mut files = vec![];
+	find_backed_up(&dir.join(BACKUP_DIR), "", &mut files);
+	files.sort();
+	files.dedup();
+	files
+}
+
+fn find_backed_up(backup_dir: &Path, prefix: &str, files: &mut Vec<String>) {
+	let entries = match std::fs::read_dir(backup_dir) {
+		Ok(entries) => entries,
+		Err(_) => return,
+	};
+	for entry in entries.filter_map(|entry| entry.ok()) {
+		let name = entry.file_name().to_string_lossy().to_string();
+		let path = entry.path();
+		if path.is_dir() {
+			find_backed_up(&path, &format!("{}{}/", prefix, name), files);
+			continue;
+		}
+		/* name.toml.2.bak is backup 2 of name.toml */
+		let file = name
+			.strip_suffix(".bak")
+			.and_then(|name| name.rsplit_once('.'))
+			.filter(|(_, index)| {
+				index
+					.parse::<usize>()
+					.is_ok_and(|index| (1..=BACKUP_COUNT).contains(&index))
+			})
+			.map(|(file, _)| file);
+		if let Some(file) = file {
+			files.push(format!("{}{}", prefix, file));
+		}
+	}
+}
+
+/* The current version becomes the newest backup, so a restore can itself be undone */
+pub fn restore_backup(dir: &Path, file: &str, backup: &Backup) -> std::io::Result<()> {
+	let contents = std::fs::read_to_string(&backup.path)?;
+	write_atomic(dir, file, &contents)
+}
+
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+	let old: Vec<&str> = old.lines().collect();
+	let new: Vec<&str> = new.lines().collect();
+
+	/* Longest common subsequence table, lcs[i][j] covers old[i..] and new[j..] */
+	let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+	for i in (0..old.len()).rev() {
+		for j in (0..new.len()).rev() {
+			lcs[i][j] = if old[i] == new[j] {
+				lcs[i + 1][j + 1] + 1
+			} else {
+				lcs[i + 1][j].max(lcs[i][j + 1])
+			};
+		}
+	}
+
+	let mut diff = vec![];
+	let (mut i, mut j) = (0, 0);
+	while i < old.len() && j < new.len() {
+		if old[i] == new[j] {
+			diff.push(DiffLine::Same(old[i].to_string()));
+			i += 1;
+			j += 1;
+		} else if lcs[i + 1][j] >= lcs[i][j + 1] {
+			diff.push(DiffLine::Removed(old[i].to_string()));
+			i += 1;
+		} else {
+			diff.push(DiffLine::Added(new[j].to_string()));
+			j += 1;
+		}
+	}
+	diff.extend(
+		old[i..]
+			.iter()
+			.map(|line| DiffLine::Removed(line.to_string())),
+	);
+	diff.extend(
+		new[j..]
+			.iter()
+			.map(|line| DiffLine::Added(line.to_string())),
+	);
+	diff
+}
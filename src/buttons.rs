@@ -0,0 +1,248 @@
+use crate::Buttons;
+use std::borrow::Cow;
+use strum::IntoEnumIterator;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ButtonCategory {
+	Keyboard,
+	Numpad,
+	Mouse,
+	Controller,
+}
+
+impl ButtonCategory {
+	pub const ALL: [ButtonCategory; 4] = [
+		ButtonCategory::Keyboard,
+		ButtonCategory::Numpad,
+		ButtonCategory::Mouse,
+		ButtonCategory::Controller,
+	];
+
+	pub fn title(self) -> &'static str {
+		match self {
+			ButtonCategory::Keyboard => "Keyboard",
+			ButtonCategory::Numpad => "Numpad",
+			ButtonCategory::Mouse => "Mouse",
+			ButtonCategory::Controller => "Controller",
+		}
+	}
+
+	pub fn buttons(self) -> impl Iterator<Item = Buttons> {
+		Buttons::iter().filter(move |button| button.category() == self)
+	}
+}
+
+impl Buttons {
+	pub fn category(self) -> ButtonCategory {
+		let name: &'static str = self.into();
+		if name.starts_with("SDL_") {
+			ButtonCategory::Controller
+		} else if name.starts_with("MOUSE_") {
+			ButtonCategory::Mouse
+		} else if name.starts_with("NUMPAD")
+			|| matches!(
+				self,
+				Buttons::MULTIPLY
+					| Buttons::ADD | Buttons::SUBTRACT
+					| Buttons::DECIMAL
+					| Buttons::DIVIDE
+			) {
+			ButtonCategory::Numpad
+		} else {
+			ButtonCategory::Keyboard
+		}
+	}
+
+	/* Name shown in the launcher, keyconfig.toml keeps using the variant name.
+	 * Punctuation moves around between layouts, so on Windows it is named after the key in
+	 * the active layout. Elsewhere it is labelled with the key on a US layout */
+	pub fn label(self) -> Cow<'static, str> {
+		match self.virtual_key().and_then(layout_key_name) {
+			Some(name) => Cow::Owned(name),
+			None => Cow::Borrowed(self.us_label()),
+		}
+	}
+
+	/* Windows virtual key of the punctuation keys, the ones a layout can rename */
+	fn virtual_key(self) -> Option<u32> {
+		Some(match self {
+			Buttons::SEMICOLON => 0xBA,
+			Buttons::EQUALS => 0xBB,
+			Buttons::COMMA => 0xBC,
+			Buttons::MINUS => 0xBD,
+			Buttons::PERIOD => 0xBE,
+			Buttons::SLASH => 0xBF,
+			Buttons::GRAVE => 0xC0,
+			Buttons::LEFTBRACKET => 0xDB,
+			Buttons::BACKSLASH => 0xDC,
+			Buttons::RIGHTBRACKET => 0xDD,
+			Buttons::APOSTROPHE => 0xDE,
+			_ => return None,
+		})
+	}
+
+	fn us_label(self) -> &'static str {
+		match self {
+			Buttons::F1 => "F1",
+			Buttons::F2 => "F2",
+			Buttons::F3 => "F3",
+			Buttons::F4 => "F4",
+			Buttons::F5 => "F5",
+			Buttons::F6 => "F6",
+			Buttons::F7 => "F7",
+			Buttons::F8 => "F8",
+			Buttons::F9 => "F9",
+			Buttons::F10 => "F10",
+			Buttons::F11 => "F11",
+			Buttons::F12 => "F12",
+			Buttons::NUM0 => "0",
+			Buttons::NUM1 => "1",
+			Buttons::NUM2 => "2",
+			Buttons::NUM3 => "3",
+			Buttons::NUM4 => "4",
+			Buttons::NUM5 => "5",
+			Buttons::NUM6 => "6",
+			Buttons::NUM7 => "7",
+			Buttons::NUM8 => "8",
+			Buttons::NUM9 => "9",
+			Buttons::Q => "Q",
+			Buttons::W => "W",
+			Buttons::E => "E",
+			Buttons::R => "R",
+			Buttons::T => "T",
+			Buttons::Y => "Y",
+			Buttons::U => "U",
+			Buttons::I => "I",
+			Buttons::O => "O",
+			Buttons::P => "P",
+			Buttons::A => "A",
+			Buttons::S => "S",
+			Buttons::D => "D",
+			Buttons::F => "F",
+			Buttons::G => "G",
+			Buttons::H => "H",
+			Buttons::J => "J",
+			Buttons::K => "K",
+			Buttons::L => "L",
+			Buttons::Z => "Z",
+			Buttons::X => "X",
+			Buttons::C => "C",
+			Buttons::V => "V",
+			Buttons::B => "B",
+			Buttons::N => "N",
+			Buttons::M => "M",
+			Buttons::UPARROW => "Up Arrow",
+			Buttons::LEFTARROW => "Left Arrow",
+			Buttons::DOWNARROW => "Down Arrow",
+			Buttons::RIGHTARROW => "Right Arrow",
+			Buttons::ENTER => "Enter",
+			Buttons::SPACE => "Space",
+			Buttons::CONTROL => "Ctrl",
+			Buttons::SHIFT => "Shift",
+			Buttons::TAB => "Tab",
+			Buttons::ALT => "Alt",
+			Buttons::ESCAPE => "Esc",
+			Buttons::BACKSPACE => "Backspace",
+			Buttons::INSERT => "Insert",
+			Buttons::DELETE => "Delete",
+			Buttons::HOME => "Home",
+			Buttons::END => "End",
+			Buttons::PAGEUP => "Page Up",
+			Buttons::PAGEDOWN => "Page Down",
+			Buttons::CAPSLOCK => "Caps Lock",
+			Buttons::MINUS => "-",
+			Buttons::EQUALS => "=",
+			Buttons::LEFTBRACKET => "[",
+			Buttons::RIGHTBRACKET => "]",
+			Buttons::BACKSLASH => "\\",
+			Buttons::SEMICOLON => ";",
+			Buttons::APOSTROPHE => "'",
+			Buttons::COMMA => ",",
+			Buttons::PERIOD => ".",
+			Buttons::SLASH => "/",
+			Buttons::GRAVE => "`",
+			Buttons::NUMPAD0 => "Numpad 0",
+			Buttons::NUMPAD1 => "Numpad 1",
+			Buttons::NUMPAD2 => "Numpad 2",
+			Buttons::NUMPAD3 => "Numpad 3",
+			Buttons::NUMPAD4 => "Numpad 4",
+			Buttons::NUMPAD5 => "Numpad 5",
+			Buttons::NUMPAD6 => "Numpad 6",
+			Buttons::NUMPAD7 => "Numpad 7",
+			Buttons::NUMPAD8 => "Numpad 8",
+			Buttons::NUMPAD9 => "Numpad 9",
+			Buttons::MULTIPLY => "Numpad *",
+			Buttons::ADD => "Numpad +",
+			Buttons::SUBTRACT => "Numpad -",
+			Buttons::DECIMAL => "Numpad .",
+			Buttons::DIVIDE => "Numpad /",
+			Buttons::MOUSE_LEFT => "Left Mouse Button",
+			Buttons::MOUSE_RIGHT => "Right Mouse Button",
+			Buttons::MOUSE_MIDDLE => "Middle Mouse Button",
+			Buttons::MOUSE_X1 => "Mouse Back",
+			Buttons::MOUSE_X2 => "Mouse Forward",
+			Buttons::MOUSE_SCROLL_UP => "Scroll Up",
+			Buttons::MOUSE_SCROLL_DOWN => "Scroll Down",
+			Buttons::SDL_A => "A Button",
+			Buttons::SDL_B => "B Button",
+			Buttons::SDL_X => "X Button",
+			Buttons::SDL_Y => "Y Button",
+			Buttons::SDL_BACK => "Back",
+			Buttons::SDL_GUIDE => "Guide",
+			Buttons::SDL_START => "Start",
+			Buttons::SDL_LSHOULDER => "Left Shoulder",
+			Buttons::SDL_LTRIGGER => "Left Trigger",
+			Buttons::SDL_RSHOULDER => "Right Shoulder",
+			Buttons::SDL_RTRIGGER => "Right Trigger",
+			Buttons::SDL_DPAD_UP => "D-Pad Up",
+			Buttons::SDL_DPAD_LEFT => "D-Pad Left",
+			Buttons::SDL_DPAD_DOWN => "D-Pad Down",
+			Buttons::SDL_DPAD_RIGHT => "D-Pad Right",
+			Buttons::SDL_MISC => "Misc",
+			Buttons::SDL_PADDLE1 => "Paddle 1",
+			Buttons::SDL_PADDLE2 => "Paddle 2",
+			Buttons::SDL_PADDLE3 => "Paddle 3",
+			Buttons::SDL_PADDLE4 => "Paddle 4",
+			Buttons::SDL_TOUCHPAD => "Touchpad",
+			Buttons::SDL_LSTICK_UP => "Left Stick Up",
+			Buttons::SDL_LSTICK_LEFT => "Left Stick Left",
+			Buttons::SDL_LSTICK_RIGHT => "Left Stick Right",
+			Buttons::SDL_LSTICK_DOWN => "Left Stick Down",
+			Buttons::SDL_LSTICK_PRESS => "Left Stick Press",
+			Buttons::SDL_RSTICK_UP => "Right Stick Up",
+			Buttons::SDL_RSTICK_LEFT => "Right Stick Left",
+			Buttons::SDL_RSTICK_RIGHT => "Right Stick Right",
+			Buttons::SDL_RSTICK_DOWN => "Right Stick Down",
+			Buttons::SDL_RSTICK_PRESS => "Right Stick Press",
+		}
+	}
+}
+
+/* Name of a virtual key's key in the active keyboard layout */
+#[cfg(windows)]
+fn layout_key_name(virtual_key: u32) -> Option<String> {
+	#[link(name = "user32")]
+	extern "system" {
+		fn MapVirtualKeyW(code: u32, map_type: u32) -> u32;
+		fn GetKeyNameTextW(lparam: i32, buffer: *mut u16, size: i32) -> i32;
+	}
+	const MAPVK_VK_TO_VSC: u32 = 0;
+	let scan_code = unsafe { MapVirtualKeyW(virtual_key, MAPVK_VK_TO_VSC) };
+	if scan_code == 0 {
+		return None;
+	}
+	let mut buffer = [0u16; 32];
+	let len = unsafe {
+		GetKeyNameTextW(
+			(scan_code << 16) as i32,
+			buffer.as_mut_ptr(),
+			buffer.len() as i32,
+		)
+	};
+	(len > 0).then(|| String::from_utf16_lossy(&buffer[..len as usize]))
+}
+
+#[cfg(not(windows))]
+fn layout_key_name(_virtual_key: u32) -> Option<String> {
+	None
+}
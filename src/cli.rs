@@ -0,0 +1,540 @@
+use launcher_egui::{
+	compat::{compatibility, Compatibility, GameInfo},
+	conflicts::find_conflicts,
+	file_stem,
+	overlap::find_overlaps,
+	pe::{change_report, compare, hex_bytes, PeError, PeImage, GAME_EXE},
+	problems::{has_problems, ProblemKind},
+	schema::{find_key, key_path, ConfigValue, SchemaDefault, CONFIG_SCHEMA},
+	AllConfig, Buttons, InternalPatch, Keyconfig,
+};
+use std::{path::Path, str::FromStr};
+
+const USAGE: &str = "usage: launcher_egui [--game-dir <folder>] <command>
+
+The game folder can also be set with the LAUNCHER_EGUI_GAME_DIR environment variable.
+
+commands:
+	init                 write config.toml and keyconfig.toml from the defaults if they
+	                     are missing
+	list                 print every setting as key = value
+	get <key>            print a single setting
+	set <key> <value>    change a single setting and save it, add --force to enable a
+	                     patch made for another game or launcher version
+	reset <key>          put a setting back to its default and save it,
+	                     config or keyconfig resets the whole file
+	toggle-patch <name>  enable or disable a patch by name or file name, add --force to
+	                     enable a patch made for another game or launcher version
+	patch-exe <output>   write a copy of DivaMegaMix.exe with the enabled patches applied,
+	                     every changed byte is listed in <output>.txt
+	check                report enabled patches that write the same bytes, expect other
+	                     original bytes or were made for another game or launcher version,
+	                     and keybinding conflicts, fails if there are any
+
+keys:
+	config.fps                                 -1 for unlimited
+	config.internalRes.x
+	config.internalRes.y
+	config.fullscreen
+	config.rumbleIntensity
+	config.<key>                               any other key in config.toml
+	keyconfig.<ACTION>                         comma separated buttons, e.g. F1,SDL_A
+	patch.<file>.<name|author|version|description|url|enabled>
+	translation.<file>.<language|author|enabled>";
+
+enum Command<'a> {
+	List,
+	Get(&'a str),
+	Set(&'a str, &'a str, bool),
+	Reset(&'a str),
+	TogglePatch(&'a str, bool),
+	PatchExe(&'a str),
+	Check,
+}
+
+enum Value<'a> {
+	Int(&'a mut i32),
+	Bool(&'a mut bool),
+	Text(&'a mut String),
+	Buttons(&'a mut Vec<Buttons>),
+	/* Keys in config.toml that are not in the schema */
+	Other(&'a mut ConfigValue),
+}
+
+pub fn run(args: &[String], dir: &Path) -> Result<(), String> {
+	let args: Vec<&str> = args.iter().map(String::as_str).collect();
+	let command = match args.as_slice() {
+		["help"] | ["--help"] | ["-h"] => {
+			println!("{}", USAGE);
+			return Ok(());
+		}
+		["init"] => return init(dir),
+		["list"] => Command::List,
+		["get", key] => Command::Get(key),
+		["set", key, value] => Command::Set(key, value, false),
+		["set", key, value, "--force"] => Command::Set(key, value, true),
+		["reset", key] => Command::Reset(key),
+		["toggle-patch", name] => Command::TogglePatch(name, false),
+		["toggle-patch", name, "--force"] => Command::TogglePatch(name, true),
+		["patch-exe", output] => Command::PatchExe(output),
+		["check"] => Command::Check,
+		_ => return Err(USAGE.to_string()),
+	};
+
+	if !dir.join("config.toml").exists() {
+		return Err(format!(
+			"config.toml not found in {}, run this from the game folder, pass --game-dir or run init",
+			dir.display()
+		));
+	}
+	/* Only these need patch addresses, so only they read the executable and scan it for
+	 * signatures, once */
+	let image = matches!(command, Command::PatchExe(_) | Command::Check)
+		.then(|| PeImage::load(&dir.join(GAME_EXE)));
+	let (mut config, problems) = match &image {
+		Some(image) => AllConfig::load_with_image(dir, image.as_ref()),
+		None => AllConfig::load(dir),
+	};
+	for problem in &problems {
+		eprintln!("warning: {}", problem);
+	}
+
+	match command {
+		Command::List => {
+			for key in keys(&config) {
+				let (_, value) = resolve(&mut config, &key)?;
+				println!("{} = {}", key, format_value(&value));
+			}
+		}
+		Command::Get(key) => {
+			let (_, value) = resolve(&mut config, key)?;
+			println!("{}", format_value(&value));
+		}
+		Command::Set(key, text, force) => {
+			let was_enabled: Vec<bool> = config.patches.iter().map(|patch| patch.enabled).collect();
+			let (file, value) = resolve(&mut config, key)?;
+			if has_problems(&problems, &file) {
+				return Err(format!(
+					"{} has problems and cannot be saved until they are fixed",
+					file
+				));
+			}
+			let invalid = |err: &str| format!("invalid value {:?} for {}: {}", text, key, err);
+			parse_value(value, text).map_err(|err| invalid(&err))?;
+			if let Some(name) = key.strip_prefix("config.") {
+				if let Some(error) = config
+					.config
+					.validate()
+					.into_iter()
+					.find(|error| error.key == name)
+				{
+					return Err(invalid(&error.message));
+				}
+			}
+			/* The same check toggle-patch makes */
+			if !force {
+				let game = game_info(&config, dir, None);
+				for (patch, _) in config
+					.patches
+					.iter()
+					.zip(was_enabled)
+					.filter(|(patch, was_enabled)| patch.enabled && !was_enabled)
+				{
+					if let Compatibility::Incompatible(reason) = compatibility(patch, game.as_ref())
+					{
+						return Err(format!(
+							"{} is incompatible, {}, pass --force to enable it anyway",
+							patch.file, reason
+						));
+					}
+				}
+			}
+			save(&mut config, dir)?;
+		}
+		Command::Reset(key) => {
+			let file = reset(&mut config, key)?;
+			if has_problems(&problems, file) {
+				return Err(format!(
+					"{} has problems and cannot be saved until they are fixed",
+					file
+				));
+			}
+			save(&mut config, dir)?;
+		}
+		Command::TogglePatch(name, force) => {
+			let game = game_info(&config, dir, None);
+			let patch = config
+				.patches
+				.iter_mut()
+				.find(|patch| patch.name == name || file_stem(&patch.file) == name)
+				.ok_or_else(|| format!("no patch named {}", name))?;
+			if has_problems(&problems, &patch.file) {
+				return Err(format!(
+					"{} has problems and cannot be saved until they are fixed",
+					patch.file
+				));
+			}
+			if !patch.enabled && !force {
+				if let Compatibility::Incompatible(reason) = compatibility(patch, game.as_ref()) {
+					return Err(format!(
+						"{} is incompatible, {}, pass --force to enable it anyway",
+						patch.file, reason
+					));
+				}
+			}
+			patch.enabled = !patch.enabled;
+			println!(
+				"{}: {}",
+				patch.name,
+				if patch.enabled { "enabled" } else { "disabled" }
+			);
+			save(&mut config, dir)?;
+		}
+		Command::PatchExe(output) => {
+			if let Some(patch) = config
+				.patches
+				.iter()
+				.find(|patch| patch.enabled && has_problems(&problems, &patch.file))
+			{
+				return Err(format!(
+					"{} has problems and cannot be applied until they are fixed",
+					patch.file
+				));
+			}
+			let exe = dir.join(GAME_EXE);
+			let output = Path::new(output);
+			if output.exists()
+				&& std::fs::canonicalize(output).ok() == std::fs::canonicalize(&exe).ok()
+			{
+				return Err(format!("{} would overwrite the game", output.display()));
+			}
+			let image = image
+				.unwrap_or_else(|| PeImage::load(&exe))
+				.map_err(|err| err.to_string())?;
+			let (data, changes) = image.apply(&config.patches).map_err(|errors| {
+				errors
+					.iter()
+					.map(|error| error.to_string())
+					.collect::<Vec<_>>()
+					.join("\n")
+			})?;
+			let report = format!("{}.txt", output.display());
+			std::fs::write(output, data)
+				.and_then(|_| std::fs::write(&report, change_report(&changes)))
+				.map_err(|err| format!("could not write {}: {}", output.display(), err))?;
+			println!(
+				"wrote {} with {} patch entries applied, changes are listed in {}",
+				output.display(),
+				changes.len(),
+				report
+			);
+		}
+		Command::Check => {
+			let image = image.unwrap_or_else(|| PeImage::load(&dir.join(GAME_EXE)));
+			let overlaps = find_overlaps(&config.patches);
+			let mismatches = original_mismatches(&config, image.as_ref());
+			let conflicts = find_conflicts(&config.keyconfig);
+			let game = game_info(&config, dir, Some(image.as_ref()));
+			let incompatible: Vec<String> = config
+				.patches
+				.iter()
+				.filter(|patch| patch.enabled)
+				.filter_map(|patch| match compatibility(patch, game.as_ref()) {
+					Compatibility::Incompatible(reason) => {
+						Some(format!("{} is incompatible, {}", patch.file, reason))
+					}
+					_ => None,
+				})
+				.collect();
+			for overlap in &overlaps {
+				println!("{}", overlap);
+			}
+			for mismatch in &mismatches {
+				println!("{}", mismatch);
+			}
+			for patch in &incompatible {
+				println!("{}", patch);
+			}
+			for conflict in &conflicts {
+				println!("keyconfig.toml: {}", conflict);
+			}
+			/* Already printed as warnings when loading */
+			let signatures = problems
+				.iter()
+				.filter(|problem| matches!(problem.kind, ProblemKind::Signature(_)))
+				.count();
+			let count = overlaps.len()
+				+ mismatches.len()
+				+ incompatible.len()
+				+ conflicts.len()
+				+ signatures;
+			match count {
+				0 => println!("no problems found"),
+				1 => return Err("found 1 problem".to_string()),
+				_ => return Err(format!("found {} problems", count)),
+			}
+		}
+	}
+	Ok(())
+}
+
+/* Only on request, any other command in the wrong folder would leave files behind */
+fn init(dir: &Path) -> Result<(), String> {
+	if !dir.is_dir() {
+		return Err(format!(
+			"{} is not a folder, run this from the game folder or pass --game-dir",
+			dir.display()
+		));
+	}
+	let created = AllConfig::create_missing(dir).map_err(|problems| {
+		problems
+			.iter()
+			.map(|problem| problem.to_string())
+			.collect::<Vec<_>>()
+			.join("\n")
+	})?;
+	if created.is_empty() {
+		println!("config.toml and keyconfig.toml already exist");
+	}
+	for file in created {
+		println!("created {} with default values", file);
+	}
+	Ok(())
+}
+
+/* The executable is only read, if it wasn't already, when a patch says which game it is for */
+fn game_info(
+	all: &AllConfig,
+	dir: &Path,
+	image: Option<Result<&PeImage, &PeError>>,
+) -> Option<GameInfo> {
+	if !all
+		.patches
+		.iter()
+		.any(|patch| patch.game_sha1.is_some() || patch.game_version.is_some())
+	{
+		return None;
+	}
+	match image {
+		Some(image) => image.ok().map(GameInfo::of),
+		None => PeImage::load(&dir.join(GAME_EXE))
+			.ok()
+			.map(|image| GameInfo::of(&image)),
+	}
+}
+
+/* Enabled patch entries whose expected original bytes are not what the executable has */
+fn original_mismatches(all: &AllConfig, image: Result<&PeImage, &PeError>) -> Vec<String> {
+	let expecting: Vec<(&str, &InternalPatch)> = all
+		.patches
+		.iter()
+		.filter(|patch| patch.enabled)
+		.flat_map(|patch| {
+			patch
+				.patches
+				.iter()
+				.filter(|entry| entry.original.is_some())
+				.map(move |entry| (patch.file.as_str(), entry))
+		})
+		.collect();
+	if expecting.is_empty() {
+		return vec![];
+	}
+	let image = match image {
+		Ok(image) => image,
+		Err(err) => return vec![err.to_string()],
+	};
+	let mut mismatches = vec![];
+	for (file, entry) in expecting {
+		match compare(image, entry) {
+			Ok(comparison) if comparison.mismatch => mismatches.push(format!(
+				"{} at {:#X} expects {}, {} has {}",
+				file,
+				entry.address,
+				hex_bytes(entry.original.as_deref().unwrap_or_default()),
+				GAME_EXE,
+				hex_bytes(&comparison.original)
+			)),
+			Ok(_) => (),
+			Err(err) => mismatches.push(format!("{} at {:#X}: {}", file, entry.address, err)),
+		}
+	}
+	mismatches
+}
+
+fn save(config: &mut AllConfig, dir: &Path) -> Result<(), String> {
+	config.save(dir).map_err(|problems| {
+		problems
+			.iter()
+			.map(|problem| problem.to_string())
+			.collect::<Vec<_>>()
+			.join("\n")
+	})
+}
+
+/* Returns the file that changed */
+fn reset(all: &mut AllConfig, key: &str) -> Result<&'static str, String> {
+	if key == "config" {
+		all.config.reset_all();
+		return Ok("config.toml");
+	}
+	if key == "keyconfig" {
+		all.keyconfig.reset_all();
+		return Ok("keyconfig.toml");
+	}
+	resolve(all, key)?;
+	let no_default = || format!("{} has no default", key);
+	if let Some(name) = key.strip_prefix("config.") {
+		find_key(name).ok_or_else(no_default)?;
+		all.config.reset(name);
+		return Ok("config.toml");
+	}
+	let name = key.strip_prefix("keyconfig.").ok_or_else(no_default)?;
+	Keyconfig::default_for(name).ok_or_else(no_default)?;
+	all.keyconfig.reset(name);
+	Ok("keyconfig.toml")
+}
+
+fn keys(config: &AllConfig) -> Vec<String> {
+	let mut keys: Vec<String> = CONFIG_SCHEMA
+		.iter()
+		.map(|key| format!("config.{}", key.key))
+		.collect();
+	for (path, _) in &config.config.other {
+		keys.push(format!("config.{}", key_path(path)));
+	}
+	for (action, _) in config.keyconfig.actions() {
+		keys.push(format!("keyconfig.{}", action));
+	}
+	for patch in &config.patches {
+		for field in ["name", "author", "version", "description", "url", "enabled"] {
+			keys.push(format!("patch.{}.{}", file_stem(&patch.file), field));
+		}
+	}
+	for translation in &config.translations {
+		for field in ["language", "author", "enabled"] {
+			keys.push(format!(
+				"translation.{}.{}",
+				file_stem(&translation.file),
+				field
+			));
+		}
+	}
+	keys
+}
+
+/* Returns the file the setting is saved to along with the setting itself */
+fn resolve<'a>(all: &'a mut AllConfig, key: &str) -> Result<(String, Value<'a>), String> {
+	let unknown = || format!("unknown key {}", key);
+
+	if let Some(name) = key.strip_prefix("config.") {
+		let config = &mut all.config;
+		let value = match find_key(name).map(|key| key.default) {
+			Some(SchemaDefault::Integer(_)) => {
+				Value::Int(config.int_mut(name).ok_or_else(unknown)?)
+			}
+			Some(SchemaDefault::Bool(_)) => Value::Bool(config.bool_mut(name).ok_or_else(unknown)?),
+			None => Value::Other(config.other_mut(name).ok_or_else(unknown)?),
+		};
+		return Ok(("config.toml".to_string(), value));
+	}
+
+	if let Some(name) = key.strip_prefix("keyconfig.") {
+		let vec = all.keyconfig.get_mut(name).ok_or_else(unknown)?;
+		return Ok(("keyconfig.toml".to_string(), Value::Buttons(vec)));
+	}
+
+	if let Some(rest) = key.strip_prefix("patch.") {
+		let (stem, field) = rest.rsplit_once('.').ok_or_else(unknown)?;
+		let patch = all
+			.patches
+			.iter_mut()
+			.find(|patch| file_stem(&patch.file) == stem)
+			.ok_or_else(|| format!("no patch file named {}", stem))?;
+		let file = patch.file.clone();
+		let value = match field {
+			"name" => Value::Text(&mut patch.name),
+			"author" => Value::Text(&mut patch.author),
+			"version" => Value::Text(&mut patch.version),
+			"description" => Value::Text(&mut patch.description),
+			"url" => Value::Text(&mut patch.url),
+			"enabled" => Value::Bool(&mut patch.enabled),
+			_ => return Err(unknown()),
+		};
+		return Ok((file, value));
+	}
+
+	if let Some(rest) = key.strip_prefix("translation.") {
+		let (stem, field) = rest.rsplit_once('.').ok_or_else(unknown)?;
+		let translation = all
+			.translations
+			.iter_mut()
+			.find(|translation| file_stem(&translation.file) == stem)
+			.ok_or_else(|| format!("no translation file named {}", stem))?;
+		let file = translation.file.clone();
+		let value = match field {
+			"language" => Value::Text(&mut translation.language),
+			"author" => Value::Text(&mut translation.author),
+			"enabled" => Value::Bool(&mut translation.enabled),
+			_ => return Err(unknown()),
+		};
+		return Ok((file, value));
+	}
+
+	Err(unknown())
+}
+
+fn format_value(value: &Value) -> String {
+	match value {
+		Value::Int(value) => value.to_string(),
+		Value::Bool(value) => value.to_string(),
+		Value::Text(value) => value.to_string(),
+		Value::Buttons(vec) => vec
+			.iter()
+			.map(|button| -> &'static str { button.into() })
+			.collect::<Vec<_>>()
+			.join(","),
+		Value::Other(ConfigValue::String(value)) => value.to_string(),
+		Value::Other(value) => value.to_toml().to_string(),
+	}
+}
+
+fn parse_value(value: Value, text: &str) -> Result<(), String> {
+	match value {
+		Value::Int(value) => {
+			*value = text
+				.parse()
+				.map_err(|_| "expected an integer".to_string())?
+		}
+		Value::Bool(value) => {
+			*value = text
+				.parse()
+				.map_err(|_| "expected true or false".to_string())?
+		}
+		Value::Text(value) => *value = text.to_string(),
+		Value::Buttons(vec) => {
+			*vec = text
+				.split(',')
+				.map(str::trim)
+				.filter(|name| !name.is_empty())
+				.map(|name| Buttons::from_str(name).map_err(|_| format!("unknown button {}", name)))
+				.collect::<Result<_, _>>()?
+		}
+		/* Strings don't need quotes, everything else is written as in TOML and keeps its type */
+		Value::Other(value) => {
+			let parsed = match text.parse::<toml_edit::Value>() {
+				Ok(parsed) => ConfigValue::from_toml(&parsed),
+				Err(_) => None,
+			};
+			*value = match (&*value, parsed) {
+				(ConfigValue::String(_), Some(ConfigValue::String(parsed))) => {
+					ConfigValue::String(parsed)
+				}
+				(ConfigValue::String(_), _) => ConfigValue::String(text.to_string()),
+				(_, Some(parsed)) if parsed.type_name() == value.type_name() => parsed,
+				_ => return Err(format!("expected {}", value.type_name())),
+			};
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,180 @@
+use crate::{
+	pe::{PeImage, GAME_EXE},
+	Patch,
+};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+pub const LAUNCHER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/* Dotted numbers like "1.0.3", missing parts count as 0 so "1.0" is "1.0.0" */
+#[derive(Debug, Clone)]
+pub struct Version(pub Vec<u64>);
+
+impl Version {
+	pub fn launcher() -> Version {
+		LAUNCHER_VERSION
+			.parse()
+			.expect("the package version is dotted numbers")
+	}
+
+	fn part(&self, i: usize) -> u64 {
+		self.0.get(i).copied().unwrap_or_default()
+	}
+}
+
+impl FromStr for Version {
+	type Err = std::num::ParseIntError;
+
+	fn from_str(text: &str) -> Result<Version, Self::Err> {
+		text.trim()
+			.split('.')
+			.map(str::parse)
+			.collect::<Result<_, _>>()
+			.map(Version)
+	}
+}
+
+impl fmt::Display for Version {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let parts: Vec<String> = self.0.iter().map(u64::to_string).collect();
+		write!(f, "{}", parts.join("."))
+	}
+}
+
+impl Ord for Version {
+	fn cmp(&self, other: &Version) -> Ordering {
+		(0..self.0.len().max(other.0.len()))
+			.map(|i| self.part(i).cmp(&other.part(i)))
+			.find(|ordering| ordering.is_ne())
+			.unwrap_or(Ordering::Equal)
+	}
+}
+
+impl PartialOrd for Version {
+	fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl PartialEq for Version {
+	fn eq(&self, other: &Version) -> bool {
+		self.cmp(other).is_eq()
+	}
+}
+
+impl Eq for Version {}
+
+/* SHA-1 of the whole executable as 40 hex digits, lowercase */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExeHash(pub String);
+
+impl FromStr for ExeHash {
+	type Err = ();
+
+	fn from_str(text: &str) -> Result<ExeHash, ()> {
+		if text.len() == 40 && text.chars().all(|c| c.is_ascii_hexdigit()) {
+			Ok(ExeHash(text.to_ascii_lowercase()))
+		} else {
+			Err(())
+		}
+	}
+}
+
+impl fmt::Display for ExeHash {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+/* What patches can target in the local executable */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GameInfo {
+	pub hash: ExeHash,
+	/* From the version resource, None if the executable has none */
+	pub version: Option<Version>,
+}
+
+impl GameInfo {
+	pub fn of(image: &PeImage) -> GameInfo {
+		GameInfo {
+			hash: ExeHash(sha1_smol::Sha1::from(&image.data).digest().to_string()),
+			version: image.file_version(),
+		}
+	}
+}
+
+impl PeImage {
+	/* The file version in the first VS_FIXEDFILEINFO, which always starts with 0xFEEF04BD */
+	pub fn file_version(&self) -> Option<Version> {
+		let start = self
+			.data
+			.chunks_exact(4)
+			.position(|word| word == [0xBD, 0x04, 0xEF, 0xFE])?
+			* 4;
+		let word = |i: usize| {
+			let bytes = self.data.get(start + i * 4..start + i * 4 + 4)?;
+			Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64)
+		};
+		let (high, low) = (word(2)?, word(3)?);
+		Some(Version(vec![
+			high >> 16,
+			high & 0xFFFF,
+			low >> 16,
+			low & 0xFFFF,
+		]))
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Compatibility {
+	Compatible,
+	/* Why the patch won't work */
+	Incompatible(String),
+	/* Why there is no way to tell */
+	Unknown(String),
+}
+
+impl fmt::Display for Compatibility {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Compatibility::Compatible => write!(f, "compatible"),
+			Compatibility::Incompatible(reason) => write!(f, "incompatible, {}", reason),
+			Compatibility::Unknown(reason) => write!(f, "unknown compatibility, {}", reason),
+		}
+	}
+}
+
+/* A hash picks one exact executable so it wins over a version when a patch gives both */
+pub fn compatibility(patch: &Patch, game: Option<&GameInfo>) -> Compatibility {
+	if let Some(min) = &patch.min_launcher_version {
+		let launcher = Version::launcher();
+		if *min > launcher {
+			return Compatibility::Incompatible(format!(
+				"needs launcher {} or newer, this is {}",
+				min, launcher
+			));
+		}
+	}
+	if patch.game_sha1.is_none() && patch.game_version.is_none() {
+		return Compatibility::Unknown("the patch doesn't say which game version it is for".into());
+	}
+	let game = match game {
+		Some(game) => game,
+		None => return Compatibility::Unknown(format!("{} could not be read", GAME_EXE)),
+	};
+	if let Some(hash) = &patch.game_sha1 {
+		return if *hash == game.hash {
+			Compatibility::Compatible
+		} else {
+			Compatibility::Incompatible(format!("made for a different {}", GAME_EXE))
+		};
+	}
+	match (&patch.game_version, &game.version) {
+		(Some(wanted), Some(found)) if wanted == found => Compatibility::Compatible,
+		(Some(wanted), Some(found)) => Compatibility::Incompatible(format!(
+			"made for game version {}, this is {}",
+			wanted, found
+		)),
+		_ => Compatibility::Unknown(format!("{} has no version", GAME_EXE)),
+	}
+}
@@ -0,0 +1,121 @@
+use crate::{
+	actions::{group_of, ActionGroup},
+	Buttons, Keyconfig,
+};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Conflict {
+	/* The same button listed twice for one action */
+	Duplicate {
+		action: String,
+		button: Buttons,
+	},
+	/* One button fires two actions that can be active at the same time */
+	Shared {
+		button: Buttons,
+		first: String,
+		second: String,
+	},
+}
+
+impl Conflict {
+	pub fn button(&self) -> Buttons {
+		match *self {
+			Conflict::Duplicate { button, .. } | Conflict::Shared { button, .. } => button,
+		}
+	}
+
+	pub fn involves(&self, action: &str) -> bool {
+		match self {
+			Conflict::Duplicate { action: other, .. } => other == action,
+			Conflict::Shared { first, second, .. } => first == action || second == action,
+		}
+	}
+
+	/* Describes the conflict from the point of view of one of its actions */
+	pub fn describe(&self, action: &str) -> String {
+		match self {
+			Conflict::Duplicate { .. } => "listed more than once".to_string(),
+			Conflict::Shared { first, second, .. } => {
+				let other = if first == action { second } else { first };
+				format!("also bound to {}", other)
+			}
+		}
+	}
+}
+
+impl fmt::Display for Conflict {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Conflict::Duplicate { action, button } => {
+				write!(f, "{:?} is listed more than once for {}", button, action)
+			}
+			Conflict::Shared {
+				button,
+				first,
+				second,
+			} => write!(f, "{:?} is bound to both {} and {}", button, first, second),
+		}
+	}
+}
+
+/* Gameplay buttons only work with the camera locked and camera buttons only with it unlocked,
+ * so those two sections may share buttons. Game state buttons and the unlock toggle work in
+ * both modes and must not share with gameplay or camera buttons. The game itself puts
+ * ADVERTISE and the unlock toggle on F3, so game state buttons may share with the toggle.
+ * Nothing is known about actions in the Other group so only duplicates are reported for them */
+fn active_locked(action: &str) -> bool {
+	match group_of(action) {
+		ActionGroup::GameState | ActionGroup::Gameplay => true,
+		ActionGroup::Camera => action == "CAMERA_UNLOCK_TOGGLE",
+		ActionGroup::Other => false,
+	}
+}
+
+fn active_unlocked(action: &str) -> bool {
+	matches!(
+		group_of(action),
+		ActionGroup::GameState | ActionGroup::Camera
+	)
+}
+
+fn is_state_and_toggle(first: &str, second: &str) -> bool {
+	group_of(first) == ActionGroup::GameState && second == "CAMERA_UNLOCK_TOGGLE"
+}
+
+pub fn can_share(first: &str, second: &str) -> bool {
+	is_state_and_toggle(first, second)
+		|| is_state_and_toggle(second, first)
+		|| !(active_locked(first) && active_locked(second)
+			|| active_unlocked(first) && active_unlocked(second))
+}
+
+pub fn find_conflicts(keyconfig: &Keyconfig) -> Vec<Conflict> {
+	let actions: Vec<(&str, &Vec<Buttons>)> = keyconfig.actions().collect();
+	let mut conflicts = vec![];
+	for (i, (action, vec)) in actions.iter().enumerate() {
+		for (j, button) in vec.iter().enumerate() {
+			if vec[..j].contains(button) {
+				let duplicate = Conflict::Duplicate {
+					action: action.to_string(),
+					button: *button,
+				};
+				if !conflicts.contains(&duplicate) {
+					conflicts.push(duplicate);
+				}
+				continue;
+			}
+			for (other, other_vec) in &actions[i + 1..] {
+				if other_vec.contains(button) && !can_share(action, other) {
+					conflicts.push(Conflict::Shared {
+						button: *button,
+						first: action.to_string(),
+						second: other.to_string(),
+					});
+				}
+			}
+		}
+	}
+	conflicts
+}
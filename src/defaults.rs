@@ -0,0 +1,110 @@
+use crate::{
+	actions::{group_of, ActionGroup},
+	model::read_bindings,
+	problems::ProblemReader,
+	schema::{find_key, SchemaDefault, CONFIG_SCHEMA},
+	Buttons, Config, Keyconfig,
+};
+use std::sync::OnceLock;
+
+/* Keyboard and controller bindings, the "Default keyboard" preset has only the keyboard half */
+pub const DEFAULT_KEYCONFIG: &str = include_str!("../defaults/keyconfig.toml");
+
+/* Keys outside CONFIG_SCHEMA have no default, resetting leaves them alone */
+impl Config {
+	/* The values the game ships with, unlike Config::default() which is all zeros */
+	pub fn game_defaults() -> Config {
+		let mut config = Config::default();
+		config.reset_all();
+		config
+	}
+
+	pub fn is_default(&self, key: &str) -> bool {
+		let mut default = self.clone();
+		default.reset(key);
+		default == *self
+	}
+
+	pub fn reset(&mut self, key: &str) {
+		match find_key(key).map(|key| key.default) {
+			Some(SchemaDefault::Integer(default)) => {
+				if let Some(value) = self.int_mut(key) {
+					*value = default;
+				}
+			}
+			Some(SchemaDefault::Bool(default)) => {
+				if let Some(value) = self.bool_mut(key) {
+					*value = default;
+				}
+			}
+			None => (),
+		}
+	}
+
+	pub fn reset_group(&mut self, group: &str) {
+		for key in CONFIG_SCHEMA.iter().filter(|key| key.group == group) {
+			self.reset(key.key);
+		}
+	}
+
+	pub fn reset_all(&mut self) {
+		for key in CONFIG_SCHEMA {
+			self.reset(key.key);
+		}
+	}
+}
+
+/* Unknown actions have no default either */
+impl Keyconfig {
+	/* The bindings the game ships with, unlike Keyconfig::default() which has none. Parsed
+	 * once, the keyconfig tab asks for them every frame */
+	pub fn game_defaults() -> &'static Keyconfig {
+		static DEFAULTS: OnceLock<Keyconfig> = OnceLock::new();
+		DEFAULTS.get_or_init(|| {
+			let doc = DEFAULT_KEYCONFIG
+				.parse::<toml_edit::Document>()
+				.expect("the default keyconfig is valid");
+			let mut problems = vec![];
+			let keyconfig = read_bindings(
+				doc.as_item(),
+				&mut ProblemReader::new("keyconfig.toml", &mut problems),
+				None,
+			);
+			assert!(problems.is_empty(), "the default keyconfig is valid");
+			keyconfig
+		})
+	}
+
+	pub fn default_for(key: &str) -> Option<Vec<Buttons>> {
+		match group_of(key) {
+			ActionGroup::Other => None,
+			_ => Keyconfig::game_defaults().get(key).cloned(),
+		}
+	}
+
+	pub fn reset(&mut self, key: &str) {
+		if let Some(default) = Keyconfig::default_for(key) {
+			self.set(key, default);
+		}
+	}
+
+	pub fn reset_group(&mut self, group: ActionGroup) {
+		self.reset_matching(|key| group_of(key) == group);
+	}
+
+	pub fn reset_all(&mut self) {
+		self.reset_matching(|_| true);
+	}
+
+	fn reset_matching(&mut self, matches: impl Fn(&str) -> bool) {
+		let defaults = Keyconfig::game_defaults();
+		for (key, vec) in self.actions_mut() {
+			if group_of(key) == ActionGroup::Other || !matches(key) {
+				continue;
+			}
+			if let Some(default) = defaults.get(key) {
+				*vec = default.clone();
+			}
+		}
+	}
+}
@@ -0,0 +1,93 @@
+use crate::{DataTypes, InternalPatch};
+use std::ops::{Range, RangeInclusive};
+
+/* How patch entries turn into the bytes written to the executable, shared by the patched
+ * copy, the original bytes view and overlap detection */
+impl DataTypes {
+	/* Size of one element, strings are written byte by byte */
+	pub fn element_size(self) -> i64 {
+		match self {
+			DataTypes::i8 | DataTypes::i8_arr | DataTypes::u8 | DataTypes::u8_arr => 1,
+			DataTypes::i16 | DataTypes::i16_arr | DataTypes::u16 | DataTypes::u16_arr => 2,
+			DataTypes::i32 | DataTypes::i32_arr | DataTypes::u32 | DataTypes::u32_arr => 4,
+			DataTypes::i64 | DataTypes::i64_arr => 8,
+			DataTypes::string => 1,
+		}
+	}
+
+	/* Values one element can hold. Patches write 0xFF into signed types as often as -1 into
+	 * unsigned ones, so each width takes both its signed and its unsigned range */
+	pub fn value_range(self) -> RangeInclusive<i64> {
+		match self {
+			DataTypes::i8 | DataTypes::i8_arr | DataTypes::u8 | DataTypes::u8_arr => {
+				i8::MIN.into()..=u8::MAX.into()
+			}
+			DataTypes::i16 | DataTypes::i16_arr | DataTypes::u16 | DataTypes::u16_arr => {
+				i16::MIN.into()..=u16::MAX.into()
+			}
+			DataTypes::i32 | DataTypes::i32_arr | DataTypes::u32 | DataTypes::u32_arr => {
+				i32::MIN.into()..=u32::MAX.into()
+			}
+			DataTypes::i64 | DataTypes::i64_arr | DataTypes::string => i64::MIN..=i64::MAX,
+		}
+	}
+}
+
+impl InternalPatch {
+	pub fn byte_len(&self) -> i64 {
+		let count = match self.data_type {
+			DataTypes::i8_arr
+			| DataTypes::u8_arr
+			| DataTypes::i16_arr
+			| DataTypes::u16_arr
+			| DataTypes::i32_arr
+			| DataTypes::u32_arr
+			| DataTypes::i64_arr => self.data_int_arr.len() as i64,
+			DataTypes::string => self.data_string.len() as i64,
+			_ => 1,
+		};
+		count * self.data_type.element_size()
+	}
+
+	/* The first value too big for the data type, bytes() would cut it short */
+	pub fn out_of_range(&self) -> Option<i64> {
+		let range = self.data_type.value_range();
+		let values = match self.data_type {
+			DataTypes::i8_arr
+			| DataTypes::u8_arr
+			| DataTypes::i16_arr
+			| DataTypes::u16_arr
+			| DataTypes::i32_arr
+			| DataTypes::u32_arr
+			| DataTypes::i64_arr => &self.data_int_arr[..],
+			DataTypes::string => &[],
+			_ => std::slice::from_ref(&self.data_int),
+		};
+		values.iter().copied().find(|value| !range.contains(value))
+	}
+
+	/* Little endian, as the game stores them */
+	pub fn bytes(&self) -> Vec<u8> {
+		let size = self.data_type.element_size() as usize;
+		match self.data_type {
+			DataTypes::i8_arr
+			| DataTypes::u8_arr
+			| DataTypes::i16_arr
+			| DataTypes::u16_arr
+			| DataTypes::i32_arr
+			| DataTypes::u32_arr
+			| DataTypes::i64_arr => self
+				.data_int_arr
+				.iter()
+				.flat_map(|data| data.to_le_bytes()[..size].to_vec())
+				.collect(),
+			DataTypes::string => self.data_string.as_bytes().to_vec(),
+			_ => self.data_int.to_le_bytes()[..size].to_vec(),
+		}
+	}
+
+	/* Addresses written, empty for empty arrays and strings */
+	pub fn span(&self) -> Range<i64> {
+		self.address..self.address.saturating_add(self.byte_len())
+	}
+}
@@ -0,0 +1,153 @@
+use crate::{buttons::ButtonCategory, Buttons, Keyconfig};
+use std::{collections::VecDeque, str::FromStr};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GamepadEvent {
+	Pressed(Buttons),
+	Released(Buttons),
+}
+
+/* A source of controller input, polled once per frame */
+pub trait Gamepad {
+	fn name(&self) -> String;
+	fn poll(&mut self) -> Vec<GamepadEvent>;
+	/* Events that will come without any new input, e.g. the rest of a script */
+	fn pending(&self) -> bool {
+		false
+	}
+}
+
+pub fn is_controller_button(button: Buttons) -> bool {
+	button.category() == ButtonCategory::Controller
+}
+
+/* Buttons currently held down */
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+pub struct GamepadState {
+	pub held: Vec<Buttons>,
+}
+
+impl GamepadState {
+	/* Returns the buttons that went down in this batch of events */
+	pub fn update(&mut self, events: &[GamepadEvent]) -> Vec<Buttons> {
+		let mut pressed = vec![];
+		for event in events {
+			match *event {
+				GamepadEvent::Pressed(button) => {
+					if !self.held.contains(&button) {
+						self.held.push(button);
+						pressed.push(button);
+					}
+				}
+				GamepadEvent::Released(button) => self.held.retain(|held| *held != button),
+			}
+		}
+		pressed
+	}
+
+	/* Actions that would fire in game with the buttons held right now */
+	pub fn active_actions<'a>(&self, keyconfig: &'a Keyconfig) -> Vec<&'a str> {
+		keyconfig
+			.actions()
+			.filter(|(_, vec)| vec.iter().any(|button| self.held.contains(button)))
+			.map(|(name, _)| name)
+			.collect()
+	}
+}
+
+enum Step {
+	Event(GamepadEvent),
+	Wait(usize),
+}
+
+/* Plays back a script so binding and the test panel work without a controller.
+ * One step per line, `wait <polls>` delays the next step by that many polls:
+ *   press SDL_A
+ *   wait 10
+ *   release SDL_A */
+pub struct VirtualGamepad {
+	steps: VecDeque<Step>,
+}
+
+impl VirtualGamepad {
+	pub fn new() -> VirtualGamepad {
+		VirtualGamepad {
+			steps: VecDeque::new(),
+		}
+	}
+
+	pub fn from_script(script: &str) -> Result<VirtualGamepad, String> {
+		let mut gamepad = VirtualGamepad::new();
+		for (i, line) in script.lines().enumerate() {
+			let line = line.split('#').next().unwrap_or_default().trim();
+			if line.is_empty() {
+				continue;
+			}
+			let error = |message: &str| format!("line {}: {}", i + 1, message);
+			let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+			let argument = argument.trim();
+			let button = || {
+				Buttons::from_str(argument)
+					.map_err(|_| error(&format!("unknown button {}", argument)))
+			};
+			match command {
+				"press" => gamepad.press(button()?),
+				"release" => gamepad.release(button()?),
+				"wait" => gamepad.wait(
+					argument
+						.parse()
+						.map_err(|_| error("wait needs a number of polls"))?,
+				),
+				_ => return Err(error(&format!("unknown command {}", command))),
+			}
+		}
+		Ok(gamepad)
+	}
+
+	pub fn press(&mut self, button: Buttons) {
+		self.steps
+			.push_back(Step::Event(GamepadEvent::Pressed(button)));
+	}
+
+	pub fn release(&mut self, button: Buttons) {
+		self.steps
+			.push_back(Step::Event(GamepadEvent::Released(button)));
+	}
+
+	pub fn wait(&mut self, polls: usize) {
+		self.steps.push_back(Step::Wait(polls));
+	}
+}
+
+impl Default for VirtualGamepad {
+	fn default() -> Self {
+		VirtualGamepad::new()
+	}
+}
+
+impl Gamepad for VirtualGamepad {
+	fn name(&self) -> String {
+		"Virtual controller".to_string()
+	}
+
+	fn pending(&self) -> bool {
+		!self.steps.is_empty()
+	}
+
+	fn poll(&mut self) -> Vec<GamepadEvent> {
+		let mut events = vec![];
+		while let Some(step) = self.steps.pop_front() {
+			match step {
+				Step::Event(event) => events.push(event),
+				Step::Wait(0) => {}
+				Step::Wait(polls) => {
+					if polls > 1 {
+						self.steps.push_front(Step::Wait(polls - 1));
+					}
+					break;
+				}
+			}
+		}
+		events
+	}
+}
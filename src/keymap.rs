@@ -0,0 +1,79 @@
+use crate::Buttons;
+use eframe::egui::{Key, Modifiers};
+
+/* Keys egui reports and the keyconfig name the game uses for them.
+ * egui has no function keys, punctuation, numpad or modifier key events, so those still
+ * come from the list and CONTROL, SHIFT and ALT are picked up from the modifier state.
+ * Escape is left out since it cancels binding */
+pub const KEY_TABLE: &[(Key, Buttons)] = &[
+	(Key::ArrowUp, Buttons::UPARROW),
+	(Key::ArrowLeft, Buttons::LEFTARROW),
+	(Key::ArrowDown, Buttons::DOWNARROW),
+	(Key::ArrowRight, Buttons::RIGHTARROW),
+	(Key::Enter, Buttons::ENTER),
+	(Key::Space, Buttons::SPACE),
+	(Key::Tab, Buttons::TAB),
+	(Key::Backspace, Buttons::BACKSPACE),
+	(Key::Insert, Buttons::INSERT),
+	(Key::Delete, Buttons::DELETE),
+	(Key::Home, Buttons::HOME),
+	(Key::End, Buttons::END),
+	(Key::PageUp, Buttons::PAGEUP),
+	(Key::PageDown, Buttons::PAGEDOWN),
+	(Key::Num0, Buttons::NUM0),
+	(Key::Num1, Buttons::NUM1),
+	(Key::Num2, Buttons::NUM2),
+	(Key::Num3, Buttons::NUM3),
+	(Key::Num4, Buttons::NUM4),
+	(Key::Num5, Buttons::NUM5),
+	(Key::Num6, Buttons::NUM6),
+	(Key::Num7, Buttons::NUM7),
+	(Key::Num8, Buttons::NUM8),
+	(Key::Num9, Buttons::NUM9),
+	(Key::A, Buttons::A),
+	(Key::B, Buttons::B),
+	(Key::C, Buttons::C),
+	(Key::D, Buttons::D),
+	(Key::E, Buttons::E),
+	(Key::F, Buttons::F),
+	(Key::G, Buttons::G),
+	(Key::H, Buttons::H),
+	(Key::I, Buttons::I),
+	(Key::J, Buttons::J),
+	(Key::K, Buttons::K),
+	(Key::L, Buttons::L),
+	(Key::M, Buttons::M),
+	(Key::N, Buttons::N),
+	(Key::O, Buttons::O),
+	(Key::P, Buttons::P),
+	(Key::Q, Buttons::Q),
+	(Key::R, Buttons::R),
+	(Key::S, Buttons::S),
+	(Key::T, Buttons::T),
+	(Key::U, Buttons::U),
+	(Key::V, Buttons::V),
+	(Key::W, Buttons::W),
+	(Key::X, Buttons::X),
+	(Key::Y, Buttons::Y),
+	(Key::Z, Buttons::Z),
+];
+
+pub fn button_for_key(key: Key) -> Option<Buttons> {
+	KEY_TABLE
+		.iter()
+		.find(|(table_key, _)| *table_key == key)
+		.map(|(_, button)| *button)
+}
+
+/* A modifier that is held now but was not when capturing started */
+pub fn button_for_modifiers(before: Modifiers, now: Modifiers) -> Option<Buttons> {
+	if now.ctrl && !before.ctrl {
+		Some(Buttons::CONTROL)
+	} else if now.shift && !before.shift {
+		Some(Buttons::SHIFT)
+	} else if now.alt && !before.alt {
+		Some(Buttons::ALT)
+	} else {
+		None
+	}
+}
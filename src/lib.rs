@@ -0,0 +1,24 @@
+pub mod actions;
+pub mod backup;
+pub mod buttons;
+pub mod compat;
+pub mod conflicts;
+pub mod defaults;
+pub mod encoding;
+pub mod gamepad;
+#[cfg(feature = "gui")]
+pub mod keymap;
+pub mod model;
+pub mod overlap;
+pub mod pe;
+#[cfg(feature = "gui")]
+pub mod physical_gamepad;
+pub mod preset;
+pub mod problems;
+pub mod profile;
+pub mod resolution;
+pub mod schema;
+pub mod signature;
+pub mod validation;
+
+pub use model::*;
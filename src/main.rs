@@ -1,18 +1,85 @@
-#![feature(derive_default_enum)]
+mod cli;
+
 use eframe::{egui, epi};
+use launcher_egui::{
+	actions::{display_name, group_of, ActionGroup},
+	backup::{self, DiffLine},
+	buttons::ButtonCategory,
+	compat::{compatibility, Compatibility, ExeHash, GameInfo, Version},
+	conflicts::{find_conflicts, Conflict},
+	file_stem,
+	gamepad::{is_controller_button, Gamepad, GamepadState, VirtualGamepad},
+	keymap,
+	overlap::{find_overlaps, Overlap},
+	patch_file,
+	pe::{self, hex_bytes, PeError, PeImage, GAME_EXE},
+	physical_gamepad::PhysicalGamepad,
+	preset::{self, Preset},
+	problems::{Problem, ProblemKind},
+	profile::{self, Profile},
+	resolution::{self, find_preset, RenderScale, RESOLUTION_PRESETS},
+	schema::{
+		key_path, ConfigKey, ConfigValue, Editor, SchemaDefault, CONFIG_GROUPS, CONFIG_SCHEMA,
+		EMPTY_VALUES,
+	},
+	signature::AddressSignature,
+	valid_file_stem,
+	validation::{self, AspectRatio, Axis, ConfigError},
+	AllConfig, Buttons, Config, DataTypes, InternalPatch, InternalTranslation, Patch,
+	SubGameStates,
+};
 use std::{
 	hash::{Hash, Hasher},
 	str::FromStr,
 };
 use strum::IntoEnumIterator;
-use strum_macros::{EnumIter, EnumString, IntoStaticStr};
 
 static mut INT_TEXT_BOX_COUNT: i32 = 0;
 
+const GAME_DIR_ENV: &str = "LAUNCHER_EGUI_GAME_DIR";
+const GAME_DIR_KEY: &str = "game_dir";
+/* Script for the virtual controller, read instead of the plugged in ones for testing bindings
+ * without a controller */
+const VIRTUAL_GAMEPAD_ENV: &str = "LAUNCHER_EGUI_VIRTUAL_GAMEPAD";
+
 fn main() {
+	let mut args: Vec<String> = std::env::args().skip(1).collect();
+	let game_dir = match take_game_dir(&mut args) {
+		Ok(game_dir) => game_dir,
+		Err(err) => {
+			eprintln!("{}", err);
+			std::process::exit(1);
+		}
+	};
+	if !args.is_empty() {
+		let dir = game_dir.unwrap_or_else(|| std::path::PathBuf::from("."));
+		if let Err(err) = cli::run(&args, &dir) {
+			eprintln!("{}", err);
+			std::process::exit(1);
+		}
+		return;
+	}
+	let gamepad = match virtual_gamepad() {
+		Ok(Some(gamepad)) => Some(gamepad),
+		/* Still usable without controllers, keyboard keys bind the same */
+		Ok(None) => match PhysicalGamepad::new() {
+			Ok(gamepad) => Some(Box::new(gamepad) as Box<dyn Gamepad>),
+			Err(err) => {
+				eprintln!("{}", err);
+				None
+			}
+		},
+		Err(err) => {
+			eprintln!("{}", err);
+			std::process::exit(1);
+		}
+	};
+
 	eframe::run_native(
 		Box::new(App {
 			current_tab: "window",
+			game_dir_override: game_dir,
+			gamepad,
 			..Default::default()
 		}),
 		eframe::NativeOptions {
@@ -22,274 +89,94 @@ fn main() {
 	)
 }
 
-#[allow(non_camel_case_types)]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, EnumString, EnumIter, Hash, IntoStaticStr)]
-pub enum Buttons {
-	F1,
-	F2,
-	F3,
-	F4,
-	F5,
-	F6,
-	F7,
-	F8,
-	F9,
-	F10,
-	F11,
-	F12,
-	NUM0,
-	NUM1,
-	NUM2,
-	NUM3,
-	NUM4,
-	NUM5,
-	NUM6,
-	NUM7,
-	NUM8,
-	NUM9,
-	Q,
-	W,
-	E,
-	R,
-	T,
-	Y,
-	U,
-	I,
-	O,
-	P,
-	A,
-	S,
-	D,
-	F,
-	G,
-	H,
-	J,
-	K,
-	L,
-	Z,
-	X,
-	C,
-	V,
-	B,
-	N,
-	M,
-	UPARROW,
-	LEFTARROW,
-	DOWNARROW,
-	RIGHTARROW,
-	ENTER,
-	SPACE,
-	CONTROL,
-	SHIFT,
-	TAB,
-	SDL_A,
-	SDL_B,
-	SDL_X,
-	SDL_Y,
-	SDL_BACK,
-	SDL_GUIDE,
-	SDL_START,
-	SDL_LSHOULDER,
-	SDL_LTRIGGER,
-	SDL_RSHOULDER,
-	SDL_RTRIGGER,
-	SDL_DPAD_UP,
-	SDL_DPAD_LEFT,
-	SDL_DPAD_DOWN,
-	SDL_DPAD_RIGHT,
-	SDL_MISC,
-	SDL_PADDLE1,
-	SDL_PADDLE2,
-	SDL_PADDLE3,
-	SDL_PADDLE4,
-	SDL_TOUCHPAD,
-	SDL_LSTICK_UP,
-	SDL_LSTICK_LEFT,
-	SDL_LSTICK_RIGHT,
-	SDL_LSTICK_DOWN,
-	SDL_LSTICK_PRESS,
-	SDL_RSTICK_UP,
-	SDL_RSTICK_LEFT,
-	SDL_RSTICK_RIGHT,
-	SDL_RSTICK_DOWN,
-	SDL_RSTICK_PRESS,
-}
-
-#[derive(Default, Debug, PartialEq, Clone)]
-pub struct AllConfig {
-	config: Config,
-	keyconfig: Keyconfig,
-	patches: Vec<Patch>,
-	translations: Vec<Translation>,
-}
-
-#[derive(Default, Debug, PartialEq, Clone)]
-pub struct Config {
-	fps: i32,
-	internal_res_x: i32,
-	internal_res_y: i32,
-	fullscreen: bool,
-	rumble_intensity: i32,
-}
-
-#[derive(Default, Debug, PartialEq, Clone)]
-pub struct Keyconfig {
-	test: Vec<Buttons>,
-	service: Vec<Buttons>,
-	advertise: Vec<Buttons>,
-	game: Vec<Buttons>,
-	data_test: Vec<Buttons>,
-	test_mode: Vec<Buttons>,
-	app_error: Vec<Buttons>,
-
-	start: Vec<Buttons>,
-	triangle: Vec<Buttons>,
-	square: Vec<Buttons>,
-	cross: Vec<Buttons>,
-	circle: Vec<Buttons>,
-	left_left: Vec<Buttons>,
-	left_right: Vec<Buttons>,
-	right_left: Vec<Buttons>,
-	right_right: Vec<Buttons>,
-
-	camera_unlock_toggle: Vec<Buttons>,
-	camera_move_forward: Vec<Buttons>,
-	camera_move_backward: Vec<Buttons>,
-	camera_move_left: Vec<Buttons>,
-	camera_move_right: Vec<Buttons>,
-	camera_move_up: Vec<Buttons>,
-	camera_move_down: Vec<Buttons>,
-	camera_rotate_cw: Vec<Buttons>,
-	camera_rotate_ccw: Vec<Buttons>,
-	camera_zoom_in: Vec<Buttons>,
-	camera_zoom_out: Vec<Buttons>,
-	camera_move_fast: Vec<Buttons>,
-	camera_move_slow: Vec<Buttons>,
-}
-
-#[derive(Default, Debug, PartialEq, Clone)]
-pub struct Patch {
-	name: String,
-	author: String,
-	enabled: bool,
-	patches: Vec<InternalPatch>,
-}
-
-#[allow(non_camel_case_types)]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, EnumString, EnumIter, IntoStaticStr)]
-pub enum DataTypes {
-	i8,
-	i8_arr,
-	u8,
-	u8_arr,
-	i16,
-	i16_arr,
-	u16,
-	u16_arr,
-	i32,
-	i32_arr,
-	u32,
-	u32_arr,
-	i64,
-	i64_arr,
-	string,
-}
-
-impl Default for DataTypes {
-	fn default() -> Self {
-		Self::u8_arr
+/* --game-dir takes priority over the environment variable */
+fn take_game_dir(args: &mut Vec<String>) -> Result<Option<std::path::PathBuf>, String> {
+	let mut game_dir = std::env::var_os(GAME_DIR_ENV).map(std::path::PathBuf::from);
+	let mut i = 0;
+	while i < args.len() {
+		if args[i] == "--game-dir" {
+			if i + 1 >= args.len() {
+				return Err("--game-dir needs a folder".to_string());
+			}
+			game_dir = Some(std::path::PathBuf::from(args.remove(i + 1)));
+			args.remove(i);
+		} else if let Some(dir) = args[i].strip_prefix("--game-dir=") {
+			game_dir = Some(std::path::PathBuf::from(dir));
+			args.remove(i);
+		} else {
+			i += 1;
+		}
 	}
+	Ok(game_dir)
 }
 
-#[derive(Default, Debug, PartialEq, Clone)]
-pub struct InternalPatch {
-	address: i64,
-	data_type: DataTypes,
-	data_int: i64,
-	data_int_arr: Vec<i64>,
-	data_string: String,
-}
-
-#[derive(Default, Debug, PartialEq, Clone)]
-pub struct Translation {
-	language: String,
-	author: String,
-	enabled: bool,
-	translations: Vec<InternalTranslation>,
-}
-
-#[allow(non_camel_case_types)]
-#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, EnumString, EnumIter, IntoStaticStr)]
-pub enum SubGameStates {
-	SUB_DATA_INITIALIZE,
-	SUB_SYSTEM_STARTUP,
-	SUB_SYSTEM_STARTUP_ERROR,
-	SUB_WARNING,
-	SUB_LOGO,
-	SUB_RATING,
-	SUB_DEMO,
-	SUB_TITLE,
-	SUB_RANKING,
-	SUB_SCORE_RANKING,
-	SUB_CM,
-	SUB_PHOTO_MODE_DEMO,
-	SUB_SELECTOR,
-	SUB_GAME_MAIN,
-	SUB_GAME_SEL,
-	SUB_STAGE_RESULT,
-	SUB_SCREEN_SHOT_SEL,
-	SUB_SCREEN_SHOT_RESULT,
-	SUB_GAME_OVER,
-	SUB_DATA_TEST_MAIN,
-	SUB_DATA_TEST_MISC,
-	SUB_DATA_TEST_OBJ,
-	SUB_DATA_TEST_STG,
-	SUB_DATA_TEST_MOT,
-	SUB_DATA_TEST_COLLISION,
-	SUB_DATA_TEST_SPR,
-	SUB_DATA_TEST_AET,
-	SUB_DATA_TEST_AUTH_3D,
-	SUB_DATA_TEST_CHR,
-	SUB_DATA_TEST_ITEM,
-	SUB_DATA_TEST_PERF,
-	SUB_DATA_TEST_PVSCRIPT,
-	SUB_DATA_TEST_PRINT,
-	SUB_DATA_TEST_CARD,
-	SUB_DATA_TEST_OPD,
-	SUB_DATA_TEST_SLIDER,
-	SUB_DATA_TEST_GLITTER,
-	SUB_DATA_TEST_GRAPHICS,
-	SUB_DATA_TEST_COLLECTION_CARD,
-	SUB_TEST_MODE_MAIN,
-	SUB_APP_ERROR,
-	#[default]
-	SUB_MAX,
+/* Waiting for a key press to bind to an action */
+pub struct KeyCapture {
+	action: String,
+	/* Modifiers already held when capturing started are not bound */
+	modifiers: egui::Modifiers,
+	warning: Option<String>,
+	/* Set from the controller, which is polled outside of the keyconfig tab */
+	pressed: Option<Buttons>,
 }
 
-#[derive(Default, Debug, PartialEq, Clone)]
-pub struct InternalTranslation {
-	old: String,
-	new: String,
-	state: SubGameStates,
-}
-
-#[derive(Default)]
-pub struct DocFileIndex {
-	doc: toml_edit::Document,
-	file: String,
-	index: usize,
+fn virtual_gamepad() -> Result<Option<Box<dyn Gamepad>>, String> {
+	let path = match std::env::var_os(VIRTUAL_GAMEPAD_ENV) {
+		Some(path) => std::path::PathBuf::from(path),
+		None => return Ok(None),
+	};
+	let script =
+		std::fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+	let gamepad = VirtualGamepad::from_script(&script)
+		.map_err(|err| format!("{}: {}", path.display(), err))?;
+	Ok(Some(Box::new(gamepad)))
 }
 
 #[derive(Default)]
 pub struct App {
 	current_tab: &'static str,
+	game_dir: std::path::PathBuf,
+	/* From --game-dir or the environment, wins over the remembered folder */
+	game_dir_override: Option<std::path::PathBuf>,
+	/* Folder shown in the picker while no game folder is open */
+	picker_dir: Option<std::path::PathBuf>,
+	picker_text: String,
 
 	config: AllConfig,
-	config_doc: toml_edit::Document,
-	keyconfig_doc: toml_edit::Document,
-	patches_docs: Vec<DocFileIndex>,
+	/* What is currently on disk, used to find unsaved changes */
+	saved: AllConfig,
 	have_translation: bool,
-	translation_docs: Vec<DocFileIndex>,
+	problems: Vec<Problem>,
+	confirm_discard: bool,
+	discard_on_exit: bool,
+	backup_file: String,
+	backup_index: usize,
+	/* The profile last applied or saved */
+	profile: Option<Profile>,
+	profile_name: String,
+	confirm_delete_profile: Option<String>,
+	key_capture: Option<KeyCapture>,
+	gamepad: Option<Box<dyn Gamepad>>,
+	gamepad_state: GamepadState,
+	new_patch_name: String,
+	/* File being renamed and the new file name */
+	rename_patch: Option<(String, String)>,
+	confirm_delete_patch: Option<String>,
+	/* Inline explanation for the last config value that was rejected, by key */
+	config_error: Option<(&'static str, String)>,
+	aspect_lock: Option<AspectRatio>,
+	render_scale: Option<RenderScale>,
+	preset_name: String,
+	/* Preset picked for import, previewed before it is applied */
+	preset_preview: Option<Result<Preset, Vec<Problem>>>,
+	/* Read when the game folder is opened to show what patches replace */
+	game_exe: Option<Result<PeImage, PeError>>,
+	/* Hash and version of the executable, for patches that say what they were made for */
+	game_info: Option<GameInfo>,
+	/* Incompatible patch the user is asked about before it is enabled, by file */
+	confirm_enable_patch: Option<String>,
+	/* Written from the defaults when the game folder was opened */
+	created_files: Vec<&'static str>,
 }
 
 impl epi::App for App {
@@ -300,8 +187,8 @@ impl epi::App for App {
 	fn setup(
 		&mut self,
 		ctx: &egui::Context,
-		frame: &epi::Frame,
-		_storage: Option<&dyn epi::Storage>,
+		_frame: &epi::Frame,
+		storage: Option<&dyn epi::Storage>,
 	) {
 		ctx.set_visuals(egui::Visuals::dark());
 
@@ -326,478 +213,35 @@ impl epi::App for App {
 		style.override_font_id = Some(egui::FontId::proportional(18.0));
 		ctx.set_style(style);
 
-		self.current_tab = "config";
-		self.have_translation = std::path::Path::new("lang.dll").exists();
-
-		if !std::path::Path::new("config.toml").exists() {
-			frame.quit();
-			return;
-		}
-		let config_str = std::fs::read_to_string("config.toml").unwrap();
-		let keyconfig_str = std::fs::read_to_string("keyconfig.toml").unwrap();
-		self.config_doc = config_str.parse::<toml_edit::Document>().unwrap();
-		self.keyconfig_doc = keyconfig_str.parse::<toml_edit::Document>().unwrap();
-		for file in std::fs::read_dir(std::path::Path::new("patches")).unwrap() {
-			let file = file.unwrap();
-			let patch_str = std::fs::read_to_string(file.path()).unwrap();
-			self.patches_docs.push(DocFileIndex {
-				doc: patch_str.parse::<toml_edit::Document>().unwrap(),
-				file: file.path().to_string_lossy().to_string(),
-				index: 0,
-			});
-		}
-
-		self.config.config.fps = self.config_doc["fps"].as_integer().unwrap() as i32;
-		self.config.config.internal_res_x =
-			self.config_doc["internalRes"]["x"].as_integer().unwrap() as i32;
-		self.config.config.internal_res_y =
-			self.config_doc["internalRes"]["y"].as_integer().unwrap() as i32;
-		self.config.config.fullscreen = self.config_doc["fullscreen"].as_bool().unwrap();
-		self.config.config.rumble_intensity =
-			self.config_doc["rumbleIntensity"].as_integer().unwrap() as i32;
-
-		read_toml_array_to_vec(&mut self.config.keyconfig.test, &self.keyconfig_doc["TEST"]);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.service,
-			&self.keyconfig_doc["SERVICE"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.advertise,
-			&self.keyconfig_doc["ADVERTISE"],
-		);
-		read_toml_array_to_vec(&mut self.config.keyconfig.game, &self.keyconfig_doc["GAME"]);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.data_test,
-			&self.keyconfig_doc["DATA_TEST"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.test_mode,
-			&self.keyconfig_doc["TEST_MODE"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.app_error,
-			&self.keyconfig_doc["APP_ERROR"],
-		);
-
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.start,
-			&self.keyconfig_doc["START"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.triangle,
-			&self.keyconfig_doc["TRIANGLE"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.square,
-			&self.keyconfig_doc["SQUARE"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.cross,
-			&self.keyconfig_doc["CROSS"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.circle,
-			&self.keyconfig_doc["CIRCLE"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.left_left,
-			&self.keyconfig_doc["LEFT_LEFT"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.left_right,
-			&self.keyconfig_doc["LEFT_RIGHT"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.right_left,
-			&self.keyconfig_doc["RIGHT_LEFT"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.right_right,
-			&self.keyconfig_doc["RIGHT_RIGHT"],
-		);
-
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_unlock_toggle,
-			&self.keyconfig_doc["CAMERA_UNLOCK_TOGGLE"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_move_forward,
-			&self.keyconfig_doc["CAMERA_MOVE_FORWARD"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_move_backward,
-			&self.keyconfig_doc["CAMERA_MOVE_BACKWARD"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_move_left,
-			&self.keyconfig_doc["CAMERA_MOVE_LEFT"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_move_right,
-			&self.keyconfig_doc["CAMERA_MOVE_RIGHT"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_move_up,
-			&self.keyconfig_doc["CAMERA_MOVE_UP"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_move_down,
-			&self.keyconfig_doc["CAMERA_MOVE_DOWN"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_rotate_cw,
-			&self.keyconfig_doc["CAMERA_ROTATE_CW"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_rotate_ccw,
-			&self.keyconfig_doc["CAMERA_ROTATE_CCW"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_zoom_in,
-			&self.keyconfig_doc["CAMERA_ZOOM_IN"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_zoom_out,
-			&self.keyconfig_doc["CAMERA_ZOOM_OUT"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_move_fast,
-			&self.keyconfig_doc["CAMERA_MOVE_FAST"],
-		);
-		read_toml_array_to_vec(
-			&mut self.config.keyconfig.camera_move_slow,
-			&self.keyconfig_doc["CAMERA_MOVE_SLOW"],
-		);
-
-		for patch_doc in &mut self.patches_docs {
-			let patch_data = Patch {
-				name: patch_doc.doc["name"].as_str().unwrap().to_string(),
-				author: patch_doc.doc["author"].as_str().unwrap().to_string(),
-				enabled: patch_doc.doc["enabled"].as_bool().unwrap(),
-				patches: vec![],
-			};
-			self.config.patches.push(patch_data.clone());
-			patch_doc.index = self
-				.config
-				.patches
-				.iter()
-				.rposition(|x| x == &patch_data)
-				.unwrap();
-
-			for table in patch_doc.doc["patch"].as_array_of_tables().unwrap() {
-				let data_type = DataTypes::from_str(table["data_type"].as_str().unwrap()).unwrap();
-				let mut patch: InternalPatch = InternalPatch::default();
-				patch.address = table["address"].as_integer().unwrap();
-				patch.data_type = data_type;
-				match data_type {
-					DataTypes::i8
-					| DataTypes::u8
-					| DataTypes::i16
-					| DataTypes::u16
-					| DataTypes::i32
-					| DataTypes::u32
-					| DataTypes::i64 => patch.data_int = table["data"].as_integer().unwrap(),
-					DataTypes::i8_arr
-					| DataTypes::u8_arr
-					| DataTypes::i16_arr
-					| DataTypes::u16_arr
-					| DataTypes::i32_arr
-					| DataTypes::u32_arr
-					| DataTypes::i64_arr => {
-						read_toml_integer_array_to_vec(&mut patch.data_int_arr, &table["data"])
-					}
-					DataTypes::string => {
-						patch.data_string = table["data"].as_str().unwrap().to_string()
-					}
-				};
-				self.config.patches.last_mut().unwrap().patches.push(patch);
-			}
-		}
-
-		if !self.have_translation {
-			return;
+		let remembered = storage
+			.and_then(|storage| storage.get_string(GAME_DIR_KEY))
+			.map(std::path::PathBuf::from);
+		let game_dir = self
+			.game_dir_override
+			.clone()
+			.or(remembered)
+			.unwrap_or_else(|| std::path::PathBuf::from("."));
+		if game_dir.join("config.toml").exists() {
+			self.open_game_dir(game_dir);
+		} else {
+			self.open_picker(&game_dir);
 		}
+	}
 
-		for file in std::fs::read_dir(std::path::Path::new("translations")).unwrap() {
-			let file = file.unwrap();
-			let translations_str = std::fs::read_to_string(file.path()).unwrap();
-			self.translation_docs.push(DocFileIndex {
-				doc: translations_str.parse::<toml_edit::Document>().unwrap(),
-				file: file.path().to_string_lossy().to_string(),
-				index: 0,
-			});
-		}
-
-		for translation_doc in &mut self.translation_docs {
-			let translation_data = Translation {
-				language: translation_doc.doc["language"]
-					.as_str()
-					.unwrap()
-					.to_string(),
-				author: translation_doc.doc["author"].as_str().unwrap().to_string(),
-				enabled: translation_doc.doc["enabled"].as_bool().unwrap(),
-				translations: vec![],
-			};
-			self.config.translations.push(translation_data.clone());
-			translation_doc.index = self
-				.config
-				.translations
-				.iter()
-				.rposition(|x| x == &translation_data)
-				.unwrap();
-
-			for table in translation_doc.doc["translation"]
-				.as_array_of_tables()
-				.unwrap()
-			{
-				let state = if table.contains_key("state") {
-					SubGameStates::from_str(table["state"].as_str().unwrap()).unwrap()
-				} else {
-					SubGameStates::SUB_MAX
-				};
-				self.config
-					.translations
-					.last_mut()
-					.unwrap()
-					.translations
-					.push(InternalTranslation {
-						old: table["old"].as_str().unwrap().to_string(),
-						new: table["new"].as_str().unwrap().to_string(),
-						state: state,
-					});
-			}
+	fn save(&mut self, storage: &mut dyn epi::Storage) {
+		if self.picker_dir.is_none() {
+			storage.set_string(GAME_DIR_KEY, self.game_dir.to_string_lossy().to_string());
 		}
 	}
 
 	fn on_exit(&mut self) {
-		if !std::path::Path::new("config.toml").exists() {
-			return;
-		}
-
-		self.config_doc["fps"] = toml_edit::value(self.config.config.fps as i64);
-		self.config_doc["internalRes"]["x"] =
-			toml_edit::value(self.config.config.internal_res_x as i64);
-		self.config_doc["internalRes"]["y"] =
-			toml_edit::value(self.config.config.internal_res_y as i64);
-		self.config_doc["fullscreen"] = toml_edit::value(self.config.config.fullscreen);
-		self.config_doc["rumbleIntensity"] =
-			toml_edit::value(self.config.config.rumble_intensity as i64);
-
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.test),
-			&mut self.keyconfig_doc["TEST"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.service),
-			&mut self.keyconfig_doc["SERVICE"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.advertise),
-			&mut self.keyconfig_doc["ADVERTISE"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.game),
-			&mut self.keyconfig_doc["GAME"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.data_test),
-			&mut self.keyconfig_doc["DATA_TEST"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.test_mode),
-			&mut self.keyconfig_doc["TEST_MODE"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.app_error),
-			&mut self.keyconfig_doc["APP_ERROR"],
-		);
-
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.start),
-			&mut self.keyconfig_doc["START"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.triangle),
-			&mut self.keyconfig_doc["TRIANGLE"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.square),
-			&mut self.keyconfig_doc["SQUARE"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.cross),
-			&mut self.keyconfig_doc["CROSS"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.circle),
-			&mut self.keyconfig_doc["CIRCLE"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.left_left),
-			&mut self.keyconfig_doc["LEFT_LEFT"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.left_right),
-			&mut self.keyconfig_doc["LEFT_RIGHT"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.right_left),
-			&mut self.keyconfig_doc["RIGHT_LEFT"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.right_right),
-			&mut self.keyconfig_doc["RIGHT_RIGHT"],
-		);
-
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_unlock_toggle),
-			&mut self.keyconfig_doc["CAMERA_UNLOCK_TOGGLE"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_move_forward),
-			&mut self.keyconfig_doc["CAMERA_MOVE_FORWARD"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_move_backward),
-			&mut self.keyconfig_doc["CAMERA_MOVE_BACKWARD"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_move_left),
-			&mut self.keyconfig_doc["CAMERA_MOVE_LEFT"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_move_right),
-			&mut self.keyconfig_doc["CAMERA_MOVE_RIGHT"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_move_up),
-			&mut self.keyconfig_doc["CAMERA_MOVE_UP"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_move_down),
-			&mut self.keyconfig_doc["CAMERA_MOVE_DOWN"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_rotate_cw),
-			&mut self.keyconfig_doc["CAMERA_ROTATE_CW"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_rotate_ccw),
-			&mut self.keyconfig_doc["CAMERA_ROTATE_CCW"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_zoom_in),
-			&mut self.keyconfig_doc["CAMERA_ZOOM_IN"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_zoom_out),
-			&mut self.keyconfig_doc["CAMERA_ZOOM_OUT"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_move_fast),
-			&mut self.keyconfig_doc["CAMERA_MOVE_FAST"],
-		);
-		read_vec_to_toml_array(
-			&buttons_vec_to_string(&self.config.keyconfig.camera_move_slow),
-			&mut self.keyconfig_doc["CAMERA_MOVE_SLOW"],
-		);
-
-		let config_str = self.config_doc.to_string();
-		let keyconfig_str = self.keyconfig_doc.to_string();
-		std::fs::write("config.toml", config_str).unwrap();
-		std::fs::write("keyconfig.toml", keyconfig_str).unwrap();
-
-		for patch_doc in &mut self.patches_docs {
-			let patch_data = &self.config.patches[patch_doc.index];
-			patch_doc.doc["name"] = toml_edit::value(&patch_data.name);
-			patch_doc.doc["author"] = toml_edit::value(&patch_data.author);
-			patch_doc.doc["enabled"] = toml_edit::value(patch_data.enabled);
-
-			let diff = patch_data.patches.len()
-				- patch_doc.doc["patch"].as_array_of_tables().unwrap().len();
-			for _ in 0..diff {
-				patch_doc.doc["patch"]
-					.as_array_of_tables_mut()
-					.unwrap()
-					.push(toml_edit::Table::default());
-			}
-
-			for (i, table) in patch_doc.doc["patch"]
-				.as_array_of_tables_mut()
-				.unwrap()
-				.iter_mut()
-				.enumerate()
-			{
-				table["address"] = toml_edit::value(patch_data.patches[i].address);
-				let data_type_str: &'static str = patch_data.patches[i].data_type.into();
-				table["data_type"] = toml_edit::value(data_type_str);
-				match patch_data.patches[i].data_type {
-					DataTypes::i8
-					| DataTypes::u8
-					| DataTypes::i16
-					| DataTypes::u16
-					| DataTypes::i32
-					| DataTypes::u32
-					| DataTypes::i64 => table["data"] = toml_edit::value(patch_data.patches[i].data_int),
-					DataTypes::i8_arr
-					| DataTypes::u8_arr
-					| DataTypes::i16_arr
-					| DataTypes::u16_arr
-					| DataTypes::i32_arr
-					| DataTypes::u32_arr
-					| DataTypes::i64_arr => {
-						read_vec_to_toml_array(
-							&patch_data.patches[i].data_int_arr,
-							&mut table["data"],
-						);
-					}
-					DataTypes::string => {
-						table["data"] = toml_edit::value(&patch_data.patches[i].data_string)
-					}
-				};
-			}
-
-			let patch_str = patch_doc.doc.to_string();
-			std::fs::write(&patch_doc.file, patch_str).unwrap();
-		}
-
-		if !self.have_translation {
-			return;
-		}
-		for translation_doc in &mut self.translation_docs {
-			let translations_data = &self.config.translations[translation_doc.index];
-			translation_doc.doc["language"] = toml_edit::value(&translations_data.language);
-			translation_doc.doc["author"] = toml_edit::value(&translations_data.author);
-			translation_doc.doc["enabled"] = toml_edit::value(translations_data.enabled);
-
-			let diff = translations_data.translations.len()
-				- translation_doc.doc["translation"]
-					.as_array_of_tables()
-					.unwrap()
-					.len();
-			for _ in 0..diff {
-				translation_doc.doc["translation"]
-					.as_array_of_tables_mut()
-					.unwrap()
-					.push(toml_edit::Table::default());
-			}
-
-			for (i, table) in translation_doc.doc["translation"]
-				.as_array_of_tables_mut()
-				.unwrap()
-				.iter_mut()
-				.enumerate()
-			{
-				table["old"] = toml_edit::value(&translations_data.translations[i].old);
-				table["new"] = toml_edit::value(&translations_data.translations[i].new);
-				if table.contains_key("state") {
-					let state_str: &'static str = translations_data.translations[i].state.into();
-					table["state"] = toml_edit::value(state_str);
+		if !self.discard_on_exit && self.picker_dir.is_none() {
+			/* Files with problems are skipped, the window is gone so they can only go to stderr */
+			if let Err(problems) = self.config.save(&self.game_dir) {
+				for problem in &problems {
+					eprintln!("{}", problem);
 				}
 			}
-
-			let translation_str = translation_doc.doc.to_string();
-			std::fs::write(&translation_doc.file, translation_str).unwrap();
 		}
 	}
 
@@ -805,19 +249,112 @@ impl epi::App for App {
 		unsafe {
 			INT_TEXT_BOX_COUNT = 0;
 		}
+		if self.picker_dir.is_some() {
+			self.draw_game_dir_picker(ctx, frame);
+			return;
+		}
+		if let Some(gamepad) = &mut self.gamepad {
+			let events = gamepad.poll();
+			let pressed = self.gamepad_state.update(&events);
+			/* A gamepad can only bind controller buttons, keyboard ones come from egui */
+			let button = pressed
+				.into_iter()
+				.find(|button| is_controller_button(*button));
+			if let (Some(capture), Some(button)) = (&mut self.key_capture, button) {
+				capture.pressed = Some(button);
+			}
+			/* The controller is only polled while drawing, so keep drawing while something is
+			 * waiting on it. Otherwise presses queue up until the next redraw */
+			if !events.is_empty()
+				|| gamepad.pending()
+				|| self.key_capture.is_some()
+				|| self.current_tab == "controller"
+			{
+				ctx.request_repaint();
+			}
+		}
 		egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
 			egui::menu::bar(ui, |ui| {
-				ui.selectable_value(&mut self.current_tab, "config", "Config");
-				ui.selectable_value(&mut self.current_tab, "keyconfig", "Keyconfig");
-				ui.selectable_value(&mut self.current_tab, "patches", "Patches");
+				ui.selectable_value(
+					&mut self.current_tab,
+					"config",
+					modified_label("Config", self.config.config != self.saved.config),
+				);
+				ui.selectable_value(
+					&mut self.current_tab,
+					"keyconfig",
+					modified_label(
+						&match find_conflicts(&self.config.keyconfig).len() {
+							0 => "Keyconfig".to_string(),
+							1 => "Keyconfig (1 conflict)".to_string(),
+							count => format!("Keyconfig ({} conflicts)", count),
+						},
+						self.config.keyconfig != self.saved.keyconfig,
+					),
+				);
+				ui.selectable_value(
+					&mut self.current_tab,
+					"patches",
+					modified_label(
+						&match find_overlaps(&self.config.patches).len() {
+							0 => "Patches".to_string(),
+							1 => "Patches (1 overlap)".to_string(),
+							count => format!("Patches ({} overlaps)", count),
+						},
+						self.config.patches != self.saved.patches,
+					),
+				);
 				if self.have_translation {
-					ui.selectable_value(&mut self.current_tab, "translation", "Translations");
+					ui.selectable_value(
+						&mut self.current_tab,
+						"translation",
+						modified_label(
+							"Translations",
+							self.config.translations != self.saved.translations,
+						),
+					);
+				}
+				ui.selectable_value(&mut self.current_tab, "controller", "Controller test");
+				ui.selectable_value(&mut self.current_tab, "backups", "Backups");
+				if !self.problems.is_empty() {
+					ui.selectable_value(
+						&mut self.current_tab,
+						"problems",
+						format!("Problems ({})", self.problems.len()),
+					);
 				}
+				ui.separator();
+				let profile_label = match &self.profile {
+					Some(profile) => modified_label(
+						&format!("Profile: {}", profile.name),
+						Profile::snapshot(&profile.name, &self.config) != *profile,
+					),
+					None => "Profile".to_string(),
+				};
+				ui.menu_button(profile_label, |ui| self.draw_profile_menu(ui));
 			});
 		});
 
 		egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
 			ui.horizontal(|ui| {
+				let size = vec2_x_modify(&mut ui.available_size(), 4.0);
+				if ui.add_sized(size, egui::Button::new("Save")).clicked() {
+					self.save_files();
+				}
+				if ui.add_sized(size, egui::Button::new("Revert")).clicked() {
+					self.revert();
+				}
+				if ui
+					.add_sized(size, egui::Button::new("Quit without saving"))
+					.clicked()
+				{
+					if self.modified_files().is_empty() {
+						self.discard_on_exit = true;
+						frame.quit();
+					} else {
+						self.confirm_discard = true;
+					}
+				}
 				if ui
 					.add_sized(ui.available_size(), egui::Button::new("Quit"))
 					.clicked()
@@ -827,6 +364,28 @@ impl epi::App for App {
 			});
 		});
 
+		if self.confirm_discard {
+			egui::Window::new("Unsaved changes")
+				.collapsible(false)
+				.resizable(false)
+				.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+				.show(ctx, |ui| {
+					ui.label("Discard unsaved changes to:");
+					for file in self.modified_files() {
+						ui.label(file);
+					}
+					ui.horizontal(|ui| {
+						if ui.button("Discard and quit").clicked() {
+							self.discard_on_exit = true;
+							frame.quit();
+						}
+						if ui.button("Cancel").clicked() {
+							self.confirm_discard = false;
+						}
+					});
+				});
+		}
+
 		egui::CentralPanel::default().show(ctx, |ui| {
 			egui::ScrollArea::both().show(ui, |ui| {
 				match self.current_tab {
@@ -834,6 +393,9 @@ impl epi::App for App {
 					"keyconfig" => self.draw_keyconfig_tab(ui),
 					"patches" => self.draw_patches_tab(ui),
 					"translation" => self.draw_translation_tab(ui),
+					"controller" => self.draw_controller_tab(ui),
+					"backups" => self.draw_backups_tab(ui),
+					"problems" => self.draw_problems_tab(ui),
 					_ => (),
 				};
 			});
@@ -842,337 +404,1373 @@ impl epi::App for App {
 }
 
 impl App {
+	fn open_game_dir(&mut self, game_dir: std::path::PathBuf) {
+		/* Remembered for the next launch, which can start in another working directory */
+		self.game_dir = std::fs::canonicalize(&game_dir).unwrap_or(game_dir);
+		self.picker_dir = None;
+		self.current_tab = "config";
+		self.have_translation = self.game_dir.join("lang.dll").exists();
+		self.game_exe = Some(PeImage::load(&self.game_dir.join(GAME_EXE)));
+		self.game_info = match &self.game_exe {
+			Some(Ok(image)) => Some(GameInfo::of(image)),
+			_ => None,
+		};
+		self.profile = None;
+		let created = AllConfig::create_missing(&self.game_dir);
+		self.revert();
+		match created {
+			Ok(files) => self.created_files = files,
+			Err(problems) => self.problems.extend(problems),
+		}
+		if !self.problems.is_empty() {
+			self.current_tab = "problems";
+		}
+	}
+
+	fn open_picker(&mut self, dir: &std::path::Path) {
+		let dir = std::fs::canonicalize(dir)
+			.or_else(|_| std::env::current_dir())
+			.unwrap_or_default();
+		self.picker_text = dir.to_string_lossy().to_string();
+		self.picker_dir = Some(dir);
+	}
+
+	fn draw_game_dir_picker(&mut self, ctx: &egui::Context, frame: &epi::Frame) {
+		let mut dir = self.picker_dir.clone().unwrap_or_default();
+		let mut open = None;
+		egui::TopBottomPanel::bottom("picker_bottom_panel").show(ctx, |ui| {
+			ui.horizontal(|ui| {
+				let size = vec2_x_modify(&mut ui.available_size(), 2.0);
+				let label = if dir.join("config.toml").exists() {
+					"Use this folder"
+				} else {
+					"Create config here"
+				};
+				if ui.add_sized(size, egui::Button::new(label)).clicked() {
+					open = Some(dir.clone());
+				}
+				if ui
+					.add_sized(ui.available_size(), egui::Button::new("Quit"))
+					.clicked()
+				{
+					frame.quit();
+				}
+			});
+		});
+		egui::CentralPanel::default().show(ctx, |ui| {
+			ui.label("config.toml was not found, choose the game folder or create a new config with the default values.");
+			ui.horizontal(|ui| {
+				if ui
+					.add_enabled(dir.parent().is_some(), egui::Button::new("Up"))
+					.clicked()
+				{
+					dir = dir.parent().unwrap().to_path_buf();
+				}
+				let response = ui.add_sized(
+					ui.available_size(),
+					egui::TextEdit::singleline(&mut self.picker_text),
+				);
+				if response.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
+					let typed = std::path::PathBuf::from(&self.picker_text);
+					if typed.is_dir() {
+						dir = typed;
+					}
+				}
+			});
+			ui.separator();
+			egui::ScrollArea::vertical().show(ui, |ui| {
+				let mut children: Vec<std::path::PathBuf> = std::fs::read_dir(&dir)
+					.map(|entries| {
+						entries
+							.filter_map(|entry| entry.ok())
+							.map(|entry| entry.path())
+							.filter(|path| path.is_dir())
+							.collect()
+					})
+					.unwrap_or_default();
+				children.sort();
+				for child in children {
+					let name = child
+						.file_name()
+						.map(|name| name.to_string_lossy().to_string())
+						.unwrap_or_default();
+					let label = if child.join("config.toml").exists() {
+						format!("{} (game folder)", name)
+					} else {
+						name
+					};
+					if ui.selectable_label(false, label).double_clicked() {
+						dir = child;
+					}
+				}
+			});
+		});
+
+		if let Some(game_dir) = open {
+			self.open_game_dir(game_dir);
+		} else if Some(&dir) != self.picker_dir.as_ref() {
+			self.open_picker(&dir);
+		}
+	}
+
+	fn save_files(&mut self) {
+		let result = self.config.save(&self.game_dir);
+		let (saved, mut problems) = self.load_files();
+		if let Err(save_problems) = result {
+			problems.extend(
+				save_problems
+					.into_iter()
+					.filter(|problem| matches!(problem.kind, ProblemKind::Write(_))),
+			);
+		}
+		self.saved = saved;
+		self.problems = problems;
+	}
+
+	/* Signatures are resolved against the executable read when the folder was opened */
+	fn load_files(&self) -> (AllConfig, Vec<Problem>) {
+		match &self.game_exe {
+			Some(image) => AllConfig::load_with_image(&self.game_dir, image.as_ref()),
+			None => AllConfig::load(&self.game_dir),
+		}
+	}
+
+	fn revert(&mut self) {
+		let (config, problems) = self.load_files();
+		self.saved = config.clone();
+		self.config = config;
+		self.problems = problems;
+	}
+
+	fn modified_files(&self) -> Vec<String> {
+		let mut files = vec![];
+		if self.config.config != self.saved.config {
+			files.push("config.toml".to_string());
+		}
+		if self.config.keyconfig != self.saved.keyconfig {
+			files.push("keyconfig.toml".to_string());
+		}
+		for patch in &self.config.patches {
+			if !self.saved.patches.contains(patch) {
+				files.push(patch.file.clone());
+			}
+		}
+		for translation in &self.config.translations {
+			if !self.saved.translations.contains(translation) {
+				files.push(translation.file.clone());
+			}
+		}
+		files
+	}
+
+	fn keyconfig_modified(&self, group: ActionGroup) -> bool {
+		self.config
+			.keyconfig
+			.group(group)
+			.any(|(name, vec)| self.saved.keyconfig.get(name) != Some(vec))
+	}
+
 	fn draw_config_tab(&mut self, ui: &mut egui::Ui) {
+		if !self.created_files.is_empty() {
+			ui.label(format!(
+				"Created {} with the default values",
+				self.created_files.join(" and ")
+			));
+		}
+		let loaded_errors = self.config.config.validate();
+		let mut defaults = Config::game_defaults();
+		defaults.other = self.config.config.other.clone();
+		if ui
+			.add_enabled(
+				self.config.config != defaults,
+				egui::Button::new("Reset all to default"),
+			)
+			.on_hover_text("Keys under Other keep their values")
+			.clicked()
+		{
+			self.config.config.reset_all();
+			self.after_config_reset();
+		}
+		for group in CONFIG_GROUPS {
+			let mut reset_group = false;
+			ui.horizontal(|ui| {
+				ui.label(egui::RichText::new(*group).strong());
+				reset_group = reset_button(
+					CONFIG_SCHEMA
+						.iter()
+						.filter(|key| key.group == *group)
+						.all(|key| self.config.config.is_default(key.key)),
+					&format!("Reset every {} setting", group.to_lowercase()),
+					ui,
+				);
+			});
+			if reset_group {
+				self.config.config.reset_group(group);
+				self.after_config_reset();
+			}
+			for key in CONFIG_SCHEMA.iter().filter(|key| key.group == *group) {
+				let is_default = self.config.config.is_default(key.key);
+				let hover = format!("Default: {}", key.default);
+				let mut reset = false;
+				match key.editor {
+					Editor::Checkbox => {
+						if let Some(value) = self.config.config.bool_mut(key.key) {
+							ui.horizontal(|ui| {
+								ui.checkbox(value, key.name).on_hover_text(key.description);
+								reset = reset_button(is_default, &hover, ui);
+							});
+						}
+					}
+					Editor::Number => reset = self.draw_number_row(key, is_default, ui),
+					Editor::Slider => {
+						if let (Some(rule), Some(value)) =
+							(key.rule, self.config.config.int_mut(key.key))
+						{
+							ui.horizontal(|ui| {
+								ui.label(key.name).on_hover_text(key.description);
+								ui.add(egui::Slider::new(value, rule.min..=rule.max).text(""));
+								reset = reset_button(is_default, &hover, ui);
+							});
+						}
+					}
+					Editor::Resolution => {
+						if key.key == validation::INTERNAL_RES_X.key {
+							self.draw_resolution_rows(ui);
+						}
+					}
+				}
+				if reset {
+					self.config.config.reset(key.key);
+					self.config_error = None;
+				}
+				config_error_label(&self.config_error, &loaded_errors, key.key, ui);
+			}
+		}
+
+		if !self.config.config.other.is_empty() {
+			ui.label(egui::RichText::new("Other").strong())
+				.on_hover_text("Keys in config.toml the launcher doesn't know about");
+			for (key, value) in &mut self.config.config.other {
+				ui.horizontal(|ui| {
+					ui.label(key_path(key));
+					config_value_editor(value, ui);
+				});
+			}
+		}
+	}
+
+	/* The resolution may have changed under the scale and the aspect ratio lock */
+	fn after_config_reset(&mut self) {
+		let config = &self.config.config;
+		self.config_error = None;
+		self.render_scale = None;
+		if self.aspect_lock.is_some() {
+			self.aspect_lock = AspectRatio::of(config.internal_res_x, config.internal_res_y);
+		}
+	}
+
+	/* Integers with a value outside their range that means something, like unlimited FPS,
+	 * get a checkbox for it. Returns true when the reset button was clicked */
+	fn draw_number_row(&mut self, key: &ConfigKey, is_default: bool, ui: &mut egui::Ui) -> bool {
+		let rule = match key.rule {
+			Some(rule) => rule,
+			None => return false,
+		};
+		let default = match key.default {
+			SchemaDefault::Integer(default) => default,
+			SchemaDefault::Bool(_) => rule.min,
+		};
+		let config_error = &mut self.config_error;
+		let value = match self.config.config.int_mut(key.key) {
+			Some(value) => value,
+			None => return false,
+		};
 		ui.horizontal(|ui| {
-			ui.label("FPS limit");
-			int_text_box(&mut self.config.config.fps, 10.0, ui);
-		});
+			ui.label(key.name).on_hover_text(key.description);
+			let mut checked = false;
+			if let Some((special, name)) = rule.special {
+				let mut label = name.to_string();
+				label[..1].make_ascii_uppercase();
+				checked = *value == special;
+				if ui.checkbox(&mut checked, label).changed() {
+					*value = if checked { special } else { default };
+				}
+			}
+			if !checked {
+				if let Some(entered) = int_text_box(&*value, 10.0, ui) {
+					match entered.and_then(|entered| rule.check(entered).map(|_| entered)) {
+						Ok(entered) => {
+							*value = entered;
+							*config_error = None;
+						}
+						Err(err) => *config_error = Some((rule.key, err)),
+					}
+				}
+			}
+			reset_button(is_default, &format!("Default: {}", key.default), ui)
+		})
+		.inner
+	}
+
+	fn draw_resolution_rows(&mut self, ui: &mut egui::Ui) {
+		let config = &mut self.config.config;
+		let config_error = &mut self.config_error;
+		let display = resolution::display_size();
+		let defaults = Config::game_defaults();
+		let mut picked = None;
+		let mut from_preset = false;
 		ui.horizontal(|ui| {
-			ui.label("Internal resolution");
-			int_text_box(&mut self.config.config.internal_res_x, 2.0, ui);
-			int_text_box(&mut self.config.config.internal_res_y, 1.0, ui);
+			ui.label("Internal resolution")
+				.on_hover_text("Size the game renders at before scaling to the window");
+			let current = (config.internal_res_x, config.internal_res_y);
+			let selected = match find_preset(current.0, current.1) {
+				Some(preset) => preset.name,
+				None if display == Some(current) => "Match display",
+				None => "Custom",
+			};
+			egui::ComboBox::from_id_source("resolution_preset")
+				.selected_text(selected)
+				.show_ui(ui, |ui| {
+					for preset in RESOLUTION_PRESETS {
+						let label = format!("{} ({}x{})", preset.name, preset.x, preset.y);
+						if ui
+							.selectable_label(selected == preset.name, label)
+							.clicked()
+						{
+							picked = Some((preset.x, preset.y));
+							from_preset = true;
+						}
+					}
+					let label = match display {
+						Some((x, y)) => format!("Match display ({}x{})", x, y),
+						None => "Match display".to_string(),
+					};
+					if ui
+						.add_enabled(
+							display.is_some(),
+							egui::SelectableLabel::new(selected == "Match display", label),
+						)
+						.on_disabled_hover_text("The display size is only known on Windows")
+						.clicked()
+					{
+						picked = display;
+						from_preset = true;
+					}
+				});
+
+			let ratio = self
+				.aspect_lock
+				.or_else(|| AspectRatio::of(config.internal_res_x, config.internal_res_y));
+			let label = match ratio {
+				Some(ratio) => format!("Lock {}:{}", ratio.width, ratio.height),
+				None => "Lock aspect ratio".to_string(),
+			};
+			let mut locked = self.aspect_lock.is_some();
+			if ui
+				.add_enabled(ratio.is_some(), egui::Checkbox::new(&mut locked, label))
+				.changed()
+			{
+				self.aspect_lock = if locked { ratio } else { None };
+			}
+			/* The size follows the slider while scaling */
+			if self.render_scale.is_some() {
+				ui.label(format!("{}x{}", current.0, current.1));
+			} else {
+				let x = int_text_box(&config.internal_res_x, 2.0, ui);
+				let y = int_text_box(&config.internal_res_y, 1.0, ui);
+				for (axis, entered) in [(Axis::X, x), (Axis::Y, y)] {
+					if let Some(entered) = entered {
+						*config_error = entered
+							.and_then(|value| config.set_resolution(axis, value, self.aspect_lock))
+							.err()
+							.map(|err| (validation::INTERNAL_RES_X.key, err));
+					}
+				}
+			}
+			let default = (defaults.internal_res_x, defaults.internal_res_y);
+			if reset_button(
+				current == default && self.render_scale.is_none(),
+				&format!("Default: {}x{}", default.0, default.1),
+				ui,
+			) {
+				picked = Some(default);
+				from_preset = true;
+			}
 		});
-		simple_checkbox("Fullscreen", &mut self.config.config.fullscreen, ui);
+
 		ui.horizontal(|ui| {
-			ui.label("Rumble Intensity");
-			ui.add(egui::Slider::new(&mut self.config.config.rumble_intensity, 0..=100).text(""));
+			let mut scaled = self.render_scale.is_some();
+			if ui.checkbox(&mut scaled, "Render scale").changed() {
+				self.render_scale = scaled.then(|| {
+					let (base_x, base_y) =
+						display.unwrap_or((config.internal_res_x, config.internal_res_y));
+					RenderScale::from_resolution(base_x, base_y, config.internal_res_x)
+				});
+			}
+			if let Some(scale) = &mut self.render_scale {
+				if ui
+					.add(egui::Slider::new(&mut scale.percent, RenderScale::PERCENT).suffix("%"))
+					.changed()
+				{
+					picked = Some(scale.size());
+				}
+				ui.label(format!("of {}x{}", scale.base_x, scale.base_y));
+			}
 		});
+
+		if let Some((x, y)) = picked {
+			match config.set_internal_res(x, y) {
+				Ok(()) => {
+					*config_error = None;
+					if self.aspect_lock.is_some() {
+						self.aspect_lock = AspectRatio::of(x, y);
+					}
+					if from_preset {
+						self.render_scale = None;
+					}
+				}
+				Err(err) => *config_error = Some((validation::INTERNAL_RES_X.key, err)),
+			}
+		}
 	}
 
 	fn draw_keyconfig_tab(&mut self, ui: &mut egui::Ui) {
-		egui::CollapsingHeader::new("Change Game State").show(ui, |ui| {
-			App::draw_keyconfig_entry(ui, "TEST".to_string(), &mut self.config.keyconfig.test);
-			App::draw_keyconfig_entry(
-				ui,
-				"SERVICE".to_string(),
-				&mut self.config.keyconfig.service,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"ADVERTISE".to_string(),
-				&mut self.config.keyconfig.advertise,
-			);
-			App::draw_keyconfig_entry(ui, "GAME".to_string(), &mut self.config.keyconfig.game);
-			App::draw_keyconfig_entry(
-				ui,
-				"DATA_TEST".to_string(),
-				&mut self.config.keyconfig.data_test,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"TEST_MODE".to_string(),
-				&mut self.config.keyconfig.test_mode,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"APP_ERROR".to_string(),
-				&mut self.config.keyconfig.app_error,
-			);
+		let mut defaults = self.config.keyconfig.clone();
+		defaults.reset_all();
+		ui.horizontal(|ui| {
+			ui.menu_button("Presets", |ui| self.draw_preset_menu(ui));
+			if ui
+				.add_enabled(
+					self.config.keyconfig != defaults,
+					egui::Button::new("Reset all to default"),
+				)
+				.on_hover_text("Actions under Other keep their bindings")
+				.clicked()
+			{
+				self.config.keyconfig = defaults.clone();
+			}
 		});
+		self.draw_preset_window(ui.ctx());
+		let conflicts = find_conflicts(&self.config.keyconfig);
+		for conflict in &conflicts {
+			ui.colored_label(egui::Color32::LIGHT_RED, conflict.to_string());
+		}
 
-		egui::CollapsingHeader::new("Gameplay").show(ui, |ui| {
-			App::draw_keyconfig_entry(ui, "START".to_string(), &mut self.config.keyconfig.start);
-			App::draw_keyconfig_entry(
-				ui,
-				"TRIANGLE".to_string(),
-				&mut self.config.keyconfig.triangle,
-			);
-			App::draw_keyconfig_entry(ui, "SQUARE".to_string(), &mut self.config.keyconfig.square);
-			App::draw_keyconfig_entry(ui, "CROSS".to_string(), &mut self.config.keyconfig.cross);
-			App::draw_keyconfig_entry(ui, "CIRCLE".to_string(), &mut self.config.keyconfig.circle);
-			App::draw_keyconfig_entry(
-				ui,
-				"LEFT_LEFT".to_string(),
-				&mut self.config.keyconfig.left_left,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"LEFT_RIGHT".to_string(),
-				&mut self.config.keyconfig.left_right,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"RIGHT_LEFT".to_string(),
-				&mut self.config.keyconfig.right_left,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"RIGHT_RIGHT".to_string(),
-				&mut self.config.keyconfig.right_right,
-			);
+		for group in ActionGroup::ALL {
+			if self.config.keyconfig.group(group).next().is_none() {
+				continue;
+			}
+			let has_conflicts = self
+				.config
+				.keyconfig
+				.group(group)
+				.any(|(name, _)| conflicts.iter().any(|conflict| conflict.involves(name)));
+			egui::CollapsingHeader::new(conflict_label(
+				modified_label(group.title(), self.keyconfig_modified(group)),
+				has_conflicts,
+			))
+			.id_source(group.title())
+			.show(ui, |ui| {
+				if group != ActionGroup::Other
+					&& reset_button(
+						self.config.keyconfig.group(group).eq(defaults.group(group)),
+						&format!("Reset every {} action", group.title().to_lowercase()),
+						ui,
+					) {
+					self.config.keyconfig.reset_group(group);
+				}
+				for (name, vec) in self.config.keyconfig.actions_mut() {
+					if group_of(name) != group {
+						continue;
+					}
+					App::draw_keyconfig_entry(
+						ui,
+						name.to_string(),
+						vec,
+						self.saved.keyconfig.get(name).map_or(&[], |saved| saved),
+						defaults.get(name).filter(|_| group != ActionGroup::Other),
+						&mut self.key_capture,
+						&conflicts,
+					);
+				}
+			});
+		}
+	}
+
+	fn draw_preset_menu(&mut self, ui: &mut egui::Ui) {
+		ui.label("Bundled");
+		for preset in preset::bundled() {
+			if ui.button(&preset.name).clicked() {
+				self.preset_preview = Some(Ok(preset));
+				ui.close_menu();
+			}
+		}
+		ui.separator();
+		let names = preset::presets(&self.game_dir);
+		if names.is_empty() {
+			ui.label(format!("No presets in the {} folder", preset::PRESET_DIR));
+		} else {
+			ui.label(format!("From the {} folder", preset::PRESET_DIR));
+		}
+		for name in &names {
+			if ui.button(name).clicked() {
+				self.preset_preview = Some(Preset::load(&self.game_dir, name));
+				ui.close_menu();
+			}
+		}
+		ui.separator();
+		ui.horizontal(|ui| {
+			ui.add(egui::TextEdit::singleline(&mut self.preset_name).hint_text("Name"));
+			let name = self.preset_name.clone();
+			let label = if names.contains(&name) {
+				"Overwrite"
+			} else {
+				"Export current"
+			};
+			if ui
+				.add_enabled(valid_file_stem(&name), egui::Button::new(label))
+				.on_disabled_hover_text("Letters, numbers, spaces, - and _ only")
+				.clicked()
+			{
+				match Preset::export(&name, &self.config.keyconfig).save(&self.game_dir) {
+					Ok(()) => self.preset_name.clear(),
+					Err(problem) => self.problems.push(problem),
+				}
+			}
 		});
+	}
 
-		egui::CollapsingHeader::new("Unlocked camera").show(ui, |ui| {
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_UNLOCK_TOGGLE".to_string(),
-				&mut self.config.keyconfig.camera_unlock_toggle,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_MOVE_FORWARD".to_string(),
-				&mut self.config.keyconfig.camera_move_forward,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_MOVE_BACKWARD".to_string(),
-				&mut self.config.keyconfig.camera_move_backward,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_MOVE_LEFT".to_string(),
-				&mut self.config.keyconfig.camera_move_left,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_MOVE_RIGHT".to_string(),
-				&mut self.config.keyconfig.camera_move_right,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_MOVE_UP".to_string(),
-				&mut self.config.keyconfig.camera_move_up,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_MOVE_DOWN".to_string(),
-				&mut self.config.keyconfig.camera_move_down,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_ROTATE_CW".to_string(),
-				&mut self.config.keyconfig.camera_rotate_cw,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_ROTATE_CCW".to_string(),
-				&mut self.config.keyconfig.camera_rotate_ccw,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_ZOOM_IN".to_string(),
-				&mut self.config.keyconfig.camera_zoom_in,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_ZOOM_OUT".to_string(),
-				&mut self.config.keyconfig.camera_zoom_out,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_MOVE_FAST".to_string(),
-				&mut self.config.keyconfig.camera_move_fast,
-			);
-			App::draw_keyconfig_entry(
-				ui,
-				"CAMERA_MOVE_SLOW".to_string(),
-				&mut self.config.keyconfig.camera_move_slow,
-			);
+	fn draw_preset_window(&mut self, ctx: &egui::Context) {
+		let preview = match &self.preset_preview {
+			Some(preview) => preview,
+			None => return,
+		};
+		let mut apply = false;
+		let mut close = false;
+		let title = match preview {
+			Ok(preset) => format!("Import {}", preset.name),
+			Err(_) => "Import preset".to_string(),
+		};
+		egui::Window::new(title)
+			.collapsible(false)
+			.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+			.show(ctx, |ui| match preview {
+				Ok(preset) => {
+					let changes = preset.changes(&self.config.keyconfig);
+					if changes.is_empty() {
+						ui.label("Nothing would change");
+					}
+					egui::Grid::new("preset_changes").show(ui, |ui| {
+						for change in &changes {
+							ui.label(display_name(&change.action))
+								.on_hover_text(&change.action);
+							ui.weak(buttons_label(&change.from));
+							ui.label("→");
+							ui.label(buttons_label(&change.to));
+							ui.end_row();
+						}
+					});
+					ui.horizontal(|ui| {
+						apply = ui
+							.add_enabled(!changes.is_empty(), egui::Button::new("Apply"))
+							.clicked();
+						close = ui.button("Cancel").clicked();
+					});
+				}
+				Err(problems) => {
+					ui.label("The preset can't be imported:");
+					for problem in problems {
+						ui.colored_label(egui::Color32::LIGHT_RED, problem.to_string());
+					}
+					close = ui.button("Close").clicked();
+				}
+			});
+		if apply {
+			if let Some(Ok(preset)) = &self.preset_preview {
+				preset.apply(&mut self.config.keyconfig);
+			}
+		}
+		if apply || close {
+			self.preset_preview = None;
+		}
+	}
+
+	fn draw_keyconfig_entry(
+		ui: &mut egui::Ui,
+		name: String,
+		vec: &mut Vec<Buttons>,
+		saved: &[Buttons],
+		default: Option<&Vec<Buttons>>,
+		key_capture: &mut Option<KeyCapture>,
+		conflicts: &[Conflict],
+	) {
+		let conflicts: Vec<&Conflict> = conflicts
+			.iter()
+			.filter(|conflict| conflict.involves(&name))
+			.collect();
+		egui::CollapsingHeader::new(conflict_label(
+			modified_label(display_name(&name), vec != saved),
+			!conflicts.is_empty(),
+		))
+			.id_source(&name)
+			.show(ui, |ui| {
+				ui.horizontal(|ui| {
+					if ui.add(egui::Button::new("+")).clicked() {
+						vec.push(Buttons::F1);
+					}
+					if ui.add(egui::Button::new("Bind")).clicked() {
+						*key_capture = Some(KeyCapture {
+							action: name.clone(),
+							modifiers: ui.input().modifiers,
+							warning: None,
+							pressed: None,
+						});
+					}
+					if ui
+						.add_enabled(!vec.is_empty(), egui::Button::new("Clear"))
+						.clicked()
+					{
+						vec.clear();
+					}
+					if let Some(default) = default {
+						if reset_button(
+							vec == default,
+							&format!("Default: {}", buttons_label(default)),
+							ui,
+						) {
+							*vec = default.clone();
+						}
+					}
+				});
+				if let Some(capture) = key_capture
+					.as_mut()
+					.filter(|capture| capture.action == name)
+				{
+					let mut done = false;
+					let input = ui.input();
+					let mut pressed = capture.pressed.take().or_else(|| {
+						keymap::button_for_modifiers(capture.modifiers, input.modifiers)
+					});
+					for event in &input.events {
+						if let egui::Event::Key {
+							key, pressed: true, ..
+						} = event
+						{
+							if *key == egui::Key::Escape {
+								done = true;
+							} else if let Some(button) = keymap::button_for_key(*key) {
+								pressed = Some(button);
+							} else {
+								capture.warning =
+									Some(format!("{:?} can't be used in keyconfig", key));
+							}
+						}
+					}
+					drop(input);
+					if let Some(button) = pressed {
+						if !vec.contains(&button) {
+							vec.push(button);
+						}
+						done = true;
+					}
+					ui.label("Press a key or controller button to bind, Escape cancels. Function keys, punctuation, numpad and mouse buttons have to be picked from the list");
+					if let Some(warning) = &capture.warning {
+						ui.colored_label(egui::Color32::YELLOW, warning);
+					}
+					if done {
+						*key_capture = None;
+					}
+				}
+				let mut edit = None;
+				let count = vec.len();
+				for (i, button) in vec.iter_mut().enumerate() {
+					ui.horizontal(|ui| {
+						button_menu(button, ui);
+						if let Some(list_edit) = list_edit_buttons(i, count, false, ui) {
+							edit = Some(list_edit);
+						}
+						for conflict in &conflicts {
+							if conflict.button() == *button {
+								ui.colored_label(
+									egui::Color32::LIGHT_RED,
+									conflict.describe(&name),
+								);
+							}
+						}
+					});
+				}
+				if let Some(edit) = edit {
+					edit.apply(vec);
+				}
+			})
+			.header_response
+			.on_hover_text(&name);
+	}
+
+	fn draw_patches_tab(&mut self, ui: &mut egui::Ui) {
+		ui.horizontal(|ui| {
+			ui.add(egui::TextEdit::singleline(&mut self.new_patch_name).hint_text("File name"));
+			let stem = self.new_patch_name.clone();
+			let taken = self.patch_file_taken(&patch_file(&stem));
+			if ui
+				.add_enabled(
+					valid_file_stem(&stem) && !taken,
+					egui::Button::new("New patch"),
+				)
+				.on_disabled_hover_text(if taken {
+					"A patch file with this name already exists"
+				} else {
+					"Letters, numbers, spaces, - and _ only"
+				})
+				.clicked()
+			{
+				self.config.patches.push(Patch::new(&stem));
+				self.new_patch_name.clear();
+			}
 		});
+
+		if let Some(Err(err)) = &self.game_exe {
+			ui.label(format!("{}, original bytes can't be shown", err));
+		}
+		let overlaps = find_overlaps(&self.config.patches);
+		for overlap in &overlaps {
+			ui.colored_label(egui::Color32::LIGHT_RED, overlap.to_string());
+		}
+
+		let mut hasher = std::collections::hash_map::DefaultHasher::new();
+		for patch in &mut self.config.patches {
+			let modified = !self.saved.patches.contains(patch);
+			let label = if patch.name.is_empty() {
+				&patch.file
+			} else {
+				&patch.name
+			};
+			let overlaps: Vec<&Overlap> = overlaps
+				.iter()
+				.filter(|overlap| overlap.involves(&patch.file))
+				.collect();
+			let compat = compatibility(patch, self.game_info.as_ref());
+			let header = conflict_label(
+				format!(
+					"{} [{}]",
+					modified_label(label, modified),
+					compatibility_badge(&compat)
+				),
+				!overlaps.is_empty(),
+			);
+			egui::CollapsingHeader::new(header)
+				.id_source(&patch.file)
+				.show(ui, |ui| {
+					ui.horizontal(|ui| {
+						ui.label(format!("File: {}", patch.file));
+						if ui.button("Rename").clicked() {
+							self.rename_patch = Some((patch.file.clone(), file_stem(&patch.file)));
+						}
+						if ui.button("Delete").clicked() {
+							self.confirm_delete_patch = Some(patch.file.clone());
+						}
+					});
+					ui.horizontal(|ui| {
+						ui.label("Name");
+						ui.text_edit_singleline(&mut patch.name);
+					});
+					ui.horizontal(|ui| {
+						ui.label("Author");
+						ui.text_edit_singleline(&mut patch.author);
+					});
+					ui.horizontal(|ui| {
+						ui.label("Version");
+						ui.text_edit_singleline(&mut patch.version);
+					});
+					ui.horizontal(|ui| {
+						ui.label("URL");
+						ui.text_edit_singleline(&mut patch.url);
+						if !patch.url.is_empty() {
+							ui.hyperlink_to("Open", &patch.url);
+						}
+					});
+					ui.label("Description");
+					ui.text_edit_multiline(&mut patch.description);
+					patch_target(patch, &compat, self.game_info.as_ref(), ui);
+					let mut enabled = patch.enabled;
+					simple_checkbox("Enable", &mut enabled, ui);
+					if enabled && !patch.enabled && matches!(compat, Compatibility::Incompatible(_))
+					{
+						self.confirm_enable_patch = Some(patch.file.clone());
+					} else {
+						patch.enabled = enabled;
+					}
+					let mut entry_edit = None;
+					let count = patch.patches.len();
+					for (i, internal_patch) in patch.patches.iter_mut().enumerate() {
+						internal_patch.address.hash(&mut hasher);
+						let entry_overlaps: Vec<String> = overlaps
+							.iter()
+							.filter(|overlap| {
+								overlap.involves_entry(&patch.file, internal_patch.address)
+							})
+							.map(|overlap| overlap.describe(&patch.file))
+							.collect();
+						let title = match (&internal_patch.signature, internal_patch.target()) {
+							(None, _) => format!("{:#X}", internal_patch.address),
+							(Some(_), Some(address)) => format!("{:#X} by signature", address),
+							(Some(_), None) => "Signature not found".to_string(),
+						};
+						let header = conflict_label(
+							title,
+							!entry_overlaps.is_empty() || internal_patch.target().is_none(),
+						);
+						egui::CollapsingHeader::new(header)
+							.id_source((&patch.file, i))
+							.show(ui, |ui| {
+								for overlap in &entry_overlaps {
+									ui.colored_label(egui::Color32::LIGHT_RED, overlap);
+								}
+								ui.horizontal(|ui| {
+									if let Some(edit) = list_edit_buttons(i, count, true, ui) {
+										entry_edit = Some(edit);
+									}
+								});
+								address_editor(internal_patch, &self.game_exe, ui);
+								ui.horizontal(|ui| {
+									ui.label("Data Type");
+									egui::ComboBox::from_id_source(hasher.finish())
+										.selected_text(format!("{:?}", internal_patch.data_type))
+										.width(ui.available_width() / 4.0)
+										.show_ui(ui, |ui| {
+											for variant in DataTypes::iter() {
+												ui.selectable_value(
+													&mut internal_patch.data_type,
+													variant,
+													format!("{:?}", variant),
+												);
+											}
+										});
+								});
+								let digits = match internal_patch.data_type {
+									DataTypes::i8
+									| DataTypes::u8
+									| DataTypes::i8_arr
+									| DataTypes::u8_arr => 2,
+									DataTypes::i16
+									| DataTypes::u16
+									| DataTypes::i16_arr
+									| DataTypes::u16_arr => 4,
+									DataTypes::i32
+									| DataTypes::u32
+									| DataTypes::i32_arr
+									| DataTypes::u32_arr => 8,
+									DataTypes::i64 | DataTypes::i64_arr => 16,
+									DataTypes::string => 0,
+								};
+								match internal_patch.data_type {
+									DataTypes::i8
+									| DataTypes::u8
+									| DataTypes::i16
+									| DataTypes::u16
+									| DataTypes::i32
+									| DataTypes::u32
+									| DataTypes::i64 => {
+										ui.horizontal(|ui| {
+											ui.label("Data");
+											hex_text_box(
+												&mut internal_patch.data_int,
+												digits,
+												5.0,
+												ui,
+											);
+										});
+									}
+									DataTypes::i8_arr
+									| DataTypes::u8_arr
+									| DataTypes::i16_arr
+									| DataTypes::u16_arr
+									| DataTypes::i32_arr
+									| DataTypes::u32_arr
+									| DataTypes::i64_arr => {
+										let mut data_edit = None;
+										let data_count = internal_patch.data_int_arr.len();
+										for (j, data) in
+											internal_patch.data_int_arr.iter_mut().enumerate()
+										{
+											ui.horizontal(|ui| {
+												ui.label(format!("Data [{}]", j));
+												hex_text_box(data, digits, 3.0, ui);
+												if let Some(edit) =
+													list_edit_buttons(j, data_count, true, ui)
+												{
+													data_edit = Some(edit);
+												}
+											});
+										}
+										if let Some(edit) = data_edit {
+											edit.apply(&mut internal_patch.data_int_arr);
+										}
+										if ui.add(egui::Button::new("+")).clicked() {
+											internal_patch.data_int_arr.push(0);
+										}
+									}
+									DataTypes::string => {
+										ui.horizontal(|ui| {
+											ui.label("Data");
+											ui.add_sized(
+												vec2_x_modify(&mut ui.available_size(), 4.0),
+												egui::TextEdit::singleline(
+													&mut internal_patch.data_string,
+												),
+											);
+										});
+									}
+								}
+								original_bytes(internal_patch, &self.game_exe, ui);
+							});
+					}
+					/* Applied after the loop since the entries are borrowed while drawing */
+					if let Some(edit) = entry_edit {
+						edit.apply(&mut patch.patches);
+					}
+					if ui.add(egui::Button::new("+")).clicked() {
+						patch.patches.push(InternalPatch::default());
+					}
+				});
+		}
+		self.draw_patch_file_windows(ui.ctx());
+	}
+
+	fn draw_patch_file_windows(&mut self, ctx: &egui::Context) {
+		if let Some((file, mut stem)) = self.rename_patch.take() {
+			let to = patch_file(&stem);
+			let valid = valid_file_stem(&stem) && (to == file || !self.patch_file_taken(&to));
+			let mut rename = false;
+			let mut close = false;
+			egui::Window::new("Rename patch file")
+				.collapsible(false)
+				.resizable(false)
+				.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+				.show(ctx, |ui| {
+					ui.label(&file);
+					ui.text_edit_singleline(&mut stem);
+					ui.horizontal(|ui| {
+						rename = ui.add_enabled(valid, egui::Button::new("Rename")).clicked();
+						close = ui.button("Cancel").clicked();
+					});
+				});
+			if rename {
+				self.rename_patch_file(&file, &to);
+			} else if !close {
+				self.rename_patch = Some((file, stem));
+			}
+		}
+
+		if let Some(file) = self.confirm_enable_patch.clone() {
+			let compat = self
+				.config
+				.patches
+				.iter()
+				.find(|patch| patch.file == file)
+				.map(|patch| compatibility(patch, self.game_info.as_ref()));
+			egui::Window::new("Enable incompatible patch")
+				.collapsible(false)
+				.resizable(false)
+				.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+				.show(ctx, |ui| {
+					if let Some(Compatibility::Incompatible(reason)) = &compat {
+						ui.label(format!("{} is {}.", file, reason));
+					}
+					ui.label("It may crash the game or break it in other ways.");
+					ui.horizontal(|ui| {
+						if ui.button("Enable anyway").clicked() {
+							if let Some(patch) = self
+								.config
+								.patches
+								.iter_mut()
+								.find(|patch| patch.file == file)
+							{
+								patch.enabled = true;
+							}
+							self.confirm_enable_patch = None;
+						}
+						if ui.button("Cancel").clicked() {
+							self.confirm_enable_patch = None;
+						}
+					});
+				});
+		}
+
+		if let Some(file) = self.confirm_delete_patch.clone() {
+			egui::Window::new("Delete patch file")
+				.collapsible(false)
+				.resizable(false)
+				.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+				.show(ctx, |ui| {
+					ui.label(format!("Delete {}?", file));
+					if self.game_dir.join(&file).exists() {
+						ui.label("A copy is kept in the backups folder.");
+					}
+					ui.horizontal(|ui| {
+						if ui.button("Delete").clicked() {
+							self.delete_patch_file(&file);
+							self.confirm_delete_patch = None;
+						}
+						if ui.button("Cancel").clicked() {
+							self.confirm_delete_patch = None;
+						}
+					});
+				});
+		}
+	}
+
+	fn patch_file_taken(&self, file: &str) -> bool {
+		self.config.patches.iter().any(|patch| patch.file == file)
+			|| self.game_dir.join(file).exists()
+	}
+
+	/* Renames and deletes happen on disk right away, only the contents wait for a save */
+	fn rename_patch_file(&mut self, from: &str, to: &str) {
+		if from == to {
+			return;
+		}
+		if self.game_dir.join(from).exists() {
+			if let Err(err) = backup::rename_file(&self.game_dir, from, to) {
+				self.push_write_problem(from, err);
+				return;
+			}
+		}
+		for patch in self
+			.config
+			.patches
+			.iter_mut()
+			.chain(self.saved.patches.iter_mut())
+		{
+			if patch.file == from {
+				patch.file = to.to_string();
+			}
+		}
+		for problem in &mut self.problems {
+			if problem.file == from {
+				problem.file = to.to_string();
+			}
+		}
 	}
 
-	fn draw_keyconfig_entry(ui: &mut egui::Ui, name: String, vec: &mut Vec<Buttons>) {
-		let mut hasher = std::collections::hash_map::DefaultHasher::new();
-		egui::CollapsingHeader::new(name).show(ui, |ui| {
-			/* Borrow checker throws a fit if this is at the bottom */
-			if ui.add(egui::Button::new("+")).clicked() {
-				vec.push(Buttons::F1);
-			}
-			for (i, button) in vec.into_iter().enumerate() {
-				button.hash(&mut hasher);
-				ui.horizontal(|ui| {
-					if ui.add(egui::Button::new("-")).clicked() {
-						//vec.remove(i);
-					}
-					egui::ComboBox::from_id_source(hasher.finish())
-						.selected_text(format!("{:?}", button))
-						.width(ui.available_width() / 4.0)
-						.show_ui(ui, |ui| {
-							for button_variant in Buttons::iter() {
-								ui.selectable_value(
-									button,
-									button_variant,
-									format!("{:?}", button_variant),
-								);
-							}
-						});
-				});
+	fn delete_patch_file(&mut self, file: &str) {
+		if self.game_dir.join(file).exists() {
+			if let Err(err) = backup::remove_file(&self.game_dir, file) {
+				self.push_write_problem(file, err);
+				return;
 			}
-		});
+		}
+		self.config.patches.retain(|patch| patch.file != file);
+		self.saved.patches.retain(|patch| patch.file != file);
+		self.problems.retain(|problem| problem.file != file);
 	}
 
-	fn draw_patches_tab(&mut self, ui: &mut egui::Ui) {
+	fn draw_translation_tab(&mut self, ui: &mut egui::Ui) {
 		let mut hasher = std::collections::hash_map::DefaultHasher::new();
-		for patch in &mut self.config.patches {
-			egui::CollapsingHeader::new(&patch.name).show(ui, |ui| {
-				ui.label(format!("Author: {}", patch.author));
-				simple_checkbox("Enable", &mut patch.enabled, ui);
-				for internal_patch in &mut patch.patches {
-					internal_patch.address.hash(&mut hasher);
-					egui::CollapsingHeader::new(format!("{:#X}", internal_patch.address)).show(
-						ui,
-						|ui| {
-							ui.horizontal(|ui| {
-								ui.label("Address");
-								hex_text_box(&mut internal_patch.address, 0, 4.0, ui);
-							});
-							ui.horizontal(|ui| {
-								ui.label("Data Type");
+		for translation in &mut self.config.translations {
+			let modified = !self.saved.translations.contains(translation);
+			egui::CollapsingHeader::new(modified_label(&translation.language, modified))
+				.id_source(&translation.file)
+				.show(ui, |ui| {
+					ui.label(format!("Author: {}", translation.author));
+					simple_checkbox("Enable", &mut translation.enabled, ui);
+					if ui.add(egui::Button::new("+")).clicked() {
+						translation
+							.translations
+							.push(InternalTranslation::default());
+					}
+					for (i, internal_translation) in
+						&mut translation.translations.iter_mut().enumerate()
+					{
+						ui.horizontal(|ui| {
+							if ui.add(egui::Button::new("-")).clicked() {
+								//vec.remove(i);
+							}
+							if internal_translation.state != SubGameStates::SUB_MAX {
+								internal_translation.old.hash(&mut hasher);
 								egui::ComboBox::from_id_source(hasher.finish())
-									.selected_text(format!("{:?}", internal_patch.data_type))
+									.selected_text(format!("{:?}", internal_translation.state))
 									.width(ui.available_width() / 4.0)
 									.show_ui(ui, |ui| {
-										for variant in DataTypes::iter() {
+										for variant in SubGameStates::iter() {
+											if variant == SubGameStates::SUB_MAX {
+												continue;
+											}
+
 											ui.selectable_value(
-												&mut internal_patch.data_type,
+												&mut internal_translation.state,
 												variant,
 												format!("{:?}", variant),
 											);
 										}
 									});
-							});
-							ui.horizontal(|ui| {
-								ui.label("Data");
-								match internal_patch.data_type {
-									DataTypes::i8 | DataTypes::u8 => {
-										hex_text_box(&mut internal_patch.data_int, 2, 5.0, ui);
-									}
-									DataTypes::i16 | DataTypes::u16 => {
-										hex_text_box(&mut internal_patch.data_int, 4, 5.0, ui);
-									}
-									DataTypes::i32 | DataTypes::u32 => {
-										hex_text_box(&mut internal_patch.data_int, 8, 5.0, ui);
-									}
-									DataTypes::i64 => {
-										hex_text_box(&mut internal_patch.data_int, 16, 5.0, ui);
-									}
-									DataTypes::i8_arr | DataTypes::u8_arr => {
-										let mut count = internal_patch.data_int_arr.len();
-										for data in &mut internal_patch.data_int_arr {
-											hex_text_box(data, 2, count as f32, ui);
-											count -= 1;
-										}
-									}
-									DataTypes::i16_arr | DataTypes::u16_arr => {
-										let mut count = internal_patch.data_int_arr.len();
-										for data in &mut internal_patch.data_int_arr {
-											hex_text_box(data, 4, count as f32, ui);
-											count -= 1;
-										}
-									}
-									DataTypes::i32_arr | DataTypes::u32_arr => {
-										let mut count = internal_patch.data_int_arr.len();
-										for data in &mut internal_patch.data_int_arr {
-											hex_text_box(data, 8, count as f32, ui);
-											count -= 1;
-										}
-									}
-									DataTypes::i64_arr => {
-										let mut count = internal_patch.data_int_arr.len();
-										for data in &mut internal_patch.data_int_arr {
-											hex_text_box(data, 16, count as f32, ui);
-											count -= 1;
-										}
-									}
-									DataTypes::string => {
-										ui.add_sized(
-											vec2_x_modify(&mut ui.available_size(), 4.0),
-											egui::TextEdit::singleline(
-												&mut internal_patch.data_string,
-											),
-										);
-									}
-								}
-							});
-						},
-					);
-				}
-			});
+							}
+							ui.add_sized(
+								vec2_x_modify(&mut ui.available_size(), 2.0),
+								egui::TextEdit::singleline(&mut internal_translation.old),
+							);
+							ui.label("->");
+							ui.add_sized(
+								ui.available_size(),
+								egui::TextEdit::singleline(&mut internal_translation.new),
+							);
+						});
+					}
+				});
 		}
 	}
 
-	fn draw_translation_tab(&mut self, ui: &mut egui::Ui) {
-		let mut hasher = std::collections::hash_map::DefaultHasher::new();
-		for translation in &mut self.config.translations {
-			egui::CollapsingHeader::new(&translation.language).show(ui, |ui| {
-				ui.label(format!("Author: {}", translation.author));
-				simple_checkbox("Enable", &mut translation.enabled, ui);
-				if ui.add(egui::Button::new("+")).clicked() {
-					translation
-						.translations
-						.push(InternalTranslation::default());
+	fn draw_backups_tab(&mut self, ui: &mut egui::Ui) {
+		let game_dir = self.game_dir.clone();
+		let dir = game_dir.as_path();
+		let mut files = vec!["config.toml".to_string(), "keyconfig.toml".to_string()];
+		files.extend(self.saved.patches.iter().map(|patch| patch.file.clone()));
+		files.extend(
+			self.saved
+				.translations
+				.iter()
+				.map(|translation| translation.file.clone()),
+		);
+		files.retain(|file| !backup::backups(dir, file).is_empty());
+		/* Deleted files are only left in the backups folder */
+		for file in backup::backed_up_files(dir) {
+			if !files.contains(&file) {
+				files.push(file);
+			}
+		}
+		if files.is_empty() {
+			ui.label("No backups yet, one is kept every time a file is saved.");
+			return;
+		}
+		if !files.contains(&self.backup_file) {
+			self.backup_file = files[0].clone();
+			self.backup_index = 0;
+		}
+
+		ui.horizontal(|ui| {
+			ui.label("File");
+			egui::ComboBox::from_id_source("backup_file")
+				.selected_text(&self.backup_file)
+				.width(ui.available_width() / 2.0)
+				.show_ui(ui, |ui| {
+					for file in &files {
+						let label = if dir.join(file).exists() {
+							file.clone()
+						} else {
+							format!("{} (deleted)", file)
+						};
+						ui.selectable_value(&mut self.backup_file, file.clone(), label);
+					}
+				});
+		});
+		let backups = backup::backups(dir, &self.backup_file);
+		ui.horizontal_wrapped(|ui| {
+			ui.label("Backup");
+			for backup in &backups {
+				ui.selectable_value(
+					&mut self.backup_index,
+					backup.index,
+					format!("#{} ({})", backup.index, format_age(backup.modified)),
+				);
+			}
+		});
+
+		let backup = match backups
+			.iter()
+			.find(|backup| backup.index == self.backup_index)
+		{
+			Some(backup) => backup,
+			None => return,
+		};
+		let modified = !self.modified_files().is_empty();
+		if ui
+			.add_enabled(!modified, egui::Button::new("Restore this backup"))
+			.on_disabled_hover_text("Save or revert your changes first")
+			.clicked()
+		{
+			match backup::restore_backup(dir, &self.backup_file, backup) {
+				Ok(()) => {
+					self.revert();
+					self.backup_index = 0;
 				}
-				for (i, internal_translation) in
-					&mut translation.translations.iter_mut().enumerate()
-				{
-					ui.horizontal(|ui| {
-						if ui.add(egui::Button::new("-")).clicked() {
-							//vec.remove(i);
-						}
-						if internal_translation.state != SubGameStates::SUB_MAX {
-							internal_translation.old.hash(&mut hasher);
-							egui::ComboBox::from_id_source(hasher.finish())
-								.selected_text(format!("{:?}", internal_translation.state))
-								.width(ui.available_width() / 4.0)
-								.show_ui(ui, |ui| {
-									for variant in SubGameStates::iter() {
-										if variant == SubGameStates::SUB_MAX {
-											continue;
-										}
+				Err(err) => {
+					let file = self.backup_file.clone();
+					self.push_write_problem(&file, err);
+				}
+			}
+			return;
+		}
 
-										ui.selectable_value(
-											&mut internal_translation.state,
-											variant,
-											format!("{:?}", variant),
-										);
-									}
-								});
+		ui.label("Restoring this backup makes these changes:");
+		let current = std::fs::read_to_string(dir.join(&self.backup_file)).unwrap_or_default();
+		let old = std::fs::read_to_string(&backup.path).unwrap_or_default();
+		for line in backup::line_diff(&current, &old) {
+			match line {
+				DiffLine::Same(line) => ui.monospace(format!("  {}", line)),
+				DiffLine::Removed(line) => {
+					ui.colored_label(egui::Color32::LIGHT_RED, format!("- {}", line))
+				}
+				DiffLine::Added(line) => {
+					ui.colored_label(egui::Color32::LIGHT_GREEN, format!("+ {}", line))
+				}
+			};
+		}
+	}
+
+	fn draw_profile_menu(&mut self, ui: &mut egui::Ui) {
+		let names = profile::profiles(&self.game_dir);
+		let modified = !self.modified_files().is_empty();
+		if names.is_empty() {
+			ui.label("No profiles yet");
+		} else if modified {
+			ui.label("Save or revert your changes before switching");
+		}
+		for name in &names {
+			if self.confirm_delete_profile.as_ref() == Some(name) {
+				ui.horizontal(|ui| {
+					ui.label(format!("Delete {}?", name));
+					if ui.button("Delete").clicked() {
+						if let Err(err) = profile::delete_profile(&self.game_dir, name) {
+							self.push_write_problem(&profile::profile_file(name), err);
 						}
-						ui.add_sized(
-							vec2_x_modify(&mut ui.available_size(), 2.0),
-							egui::TextEdit::singleline(&mut internal_translation.old),
-						);
-						ui.label("->");
-						ui.add_sized(
-							ui.available_size(),
-							egui::TextEdit::singleline(&mut internal_translation.new),
-						);
-					});
+						if self.profile.as_ref().map(|profile| &profile.name) == Some(name) {
+							self.profile = None;
+						}
+						self.confirm_delete_profile = None;
+					}
+					if ui.button("Cancel").clicked() {
+						self.confirm_delete_profile = None;
+					}
+				});
+				continue;
+			}
+			ui.horizontal(|ui| {
+				if ui.add_enabled(!modified, egui::Button::new(name)).clicked() {
+					self.apply_profile(name);
+					ui.close_menu();
+				}
+				if ui.button("Delete").clicked() {
+					self.confirm_delete_profile = Some(name.clone());
 				}
 			});
 		}
+
+		ui.separator();
+		ui.horizontal(|ui| {
+			ui.add(egui::TextEdit::singleline(&mut self.profile_name).hint_text("Name"));
+			let name = self.profile_name.clone();
+			let label = if names.contains(&name) {
+				"Overwrite"
+			} else {
+				"Save current"
+			};
+			if ui
+				.add_enabled(valid_file_stem(&name), egui::Button::new(label))
+				.on_disabled_hover_text("Letters, numbers, spaces, - and _ only")
+				.clicked()
+			{
+				let profile = Profile::snapshot(&name, &self.config);
+				match profile.save(&self.game_dir) {
+					Ok(()) => {
+						self.profile = Some(profile);
+						self.profile_name.clear();
+					}
+					Err(problem) => self.problems.push(problem),
+				}
+			}
+		});
+	}
+
+	/* Applying writes straight to the game files so the profile takes effect at once */
+	fn apply_profile(&mut self, name: &str) {
+		match Profile::load(&self.game_dir, name) {
+			Ok(profile) => {
+				profile.apply(&mut self.config);
+				self.save_files();
+				self.profile = Some(profile);
+			}
+			Err(problems) => {
+				self.problems.extend(problems);
+				self.current_tab = "problems";
+			}
+		}
+	}
+
+	fn push_write_problem(&mut self, file: &str, err: std::io::Error) {
+		self.problems.push(Problem {
+			file: file.to_string(),
+			key: String::new(),
+			line_col: None,
+			kind: ProblemKind::Write(err.to_string()),
+		});
+	}
+
+	fn draw_controller_tab(&mut self, ui: &mut egui::Ui) {
+		let gamepad = match &self.gamepad {
+			Some(gamepad) => gamepad,
+			None => {
+				ui.label(format!(
+					"Controllers could not be read on this system. Set {} to a script file to test with a virtual controller.",
+					VIRTUAL_GAMEPAD_ENV
+				));
+				return;
+			}
+		};
+		ui.label(gamepad.name());
+		ui.label(format!("Held: {}", buttons_label(&self.gamepad_state.held)));
+		ui.separator();
+
+		let active = self.gamepad_state.active_actions(&self.config.keyconfig);
+		for group in ActionGroup::ALL {
+			if self.config.keyconfig.group(group).next().is_none() {
+				continue;
+			}
+			ui.label(group.title());
+			ui.horizontal_wrapped(|ui| {
+				for (name, _) in self.config.keyconfig.group(group) {
+					if active.contains(&name) {
+						ui.colored_label(egui::Color32::LIGHT_GREEN, display_name(name));
+					} else {
+						ui.weak(display_name(name));
+					}
+				}
+			});
+		}
+	}
+
+	fn draw_problems_tab(&mut self, ui: &mut egui::Ui) {
+		ui.label("Files with problems are not saved until they are fixed. Values out of range can be corrected in the Config tab and saved.");
+		/* dedup only removes neighbours, a file can have problems from loading and from resolving signatures */
+		let mut files: Vec<&String> = self.problems.iter().map(|problem| &problem.file).collect();
+		files.sort();
+		files.dedup();
+		for file in files {
+			egui::CollapsingHeader::new(file)
+				.default_open(true)
+				.show(ui, |ui| {
+					for problem in self.problems.iter().filter(|problem| &problem.file == file) {
+						ui.colored_label(egui::Color32::LIGHT_RED, problem.to_string());
+					}
+				});
+		}
 	}
 }
 
 static mut EDIT_STRING: Option<String> = None;
 
+/* Returns what was entered once Enter is pressed, the caller decides whether to keep it */
 #[allow(unused_assignments)]
-fn int_text_box<T: num::Integer>(value: &mut T, size: f32, ui: &mut egui::Ui)
+fn int_text_box<T: num::Integer>(
+	value: &T,
+	size: f32,
+	ui: &mut egui::Ui,
+) -> Option<Result<T, String>>
 where
 	T: std::fmt::Display,
 	T: FromStr,
 	<T as FromStr>::Err: std::fmt::Debug,
 {
+	let mut entered = None;
 	let mut count = 0;
 	unsafe {
 		count = INT_TEXT_BOX_COUNT;
@@ -1190,11 +1788,12 @@ where
 		);
 		if ui.input().key_pressed(egui::Key::Enter) {
 			ui.memory().surrender_focus(kb_edit_id);
+			entered = Some(
+				text.trim()
+					.parse::<T>()
+					.map_err(|_| format!("{:?} is not a whole number", text.trim())),
+			);
 			unsafe {
-				let parsed = text.parse::<T>();
-				if parsed.is_ok() {
-					*value = parsed.unwrap();
-				}
 				EDIT_STRING = None;
 			}
 		} else {
@@ -1215,6 +1814,7 @@ where
 	unsafe {
 		INT_TEXT_BOX_COUNT += 1;
 	}
+	entered
 }
 
 #[allow(unused_assignments)]
@@ -1278,61 +1878,408 @@ where
 	}
 }
 
-fn simple_checkbox(label: &str, value: &mut bool, ui: &mut egui::Ui) {
+#[derive(Clone, Copy)]
+enum ListEdit {
+	Duplicate(usize),
+	Remove(usize),
+	MoveUp(usize),
+	MoveDown(usize),
+}
+
+impl ListEdit {
+	fn apply<T: Clone>(self, vec: &mut Vec<T>) {
+		match self {
+			ListEdit::Duplicate(i) => vec.insert(i + 1, vec[i].clone()),
+			ListEdit::Remove(i) => {
+				vec.remove(i);
+			}
+			ListEdit::MoveUp(i) => vec.swap(i - 1, i),
+			ListEdit::MoveDown(i) => vec.swap(i, i + 1),
+		}
+	}
+}
+
+fn list_edit_buttons(i: usize, len: usize, duplicate: bool, ui: &mut egui::Ui) -> Option<ListEdit> {
+	let mut edit = None;
+	if ui.add_enabled(i > 0, egui::Button::new("↑")).clicked() {
+		edit = Some(ListEdit::MoveUp(i));
+	}
+	if ui
+		.add_enabled(i + 1 < len, egui::Button::new("↓"))
+		.clicked()
+	{
+		edit = Some(ListEdit::MoveDown(i));
+	}
+	if duplicate && ui.button("Duplicate").clicked() {
+		edit = Some(ListEdit::Duplicate(i));
+	}
+	if ui.button("-").clicked() {
+		edit = Some(ListEdit::Remove(i));
+	}
+	edit
+}
+
+fn format_age(time: Option<std::time::SystemTime>) -> String {
+	let secs = match time.and_then(|time| time.elapsed().ok()) {
+		Some(elapsed) => elapsed.as_secs(),
+		None => return "unknown time".to_string(),
+	};
+	match secs {
+		0..=59 => "just now".to_string(),
+		60..=3599 => format!("{} min ago", secs / 60),
+		3600..=86399 => format!("{} h ago", secs / 3600),
+		_ => format!("{} days ago", secs / 86400),
+	}
+}
+
+fn modified_label(label: &str, modified: bool) -> String {
+	if modified {
+		format!("{} *", label)
+	} else {
+		label.to_string()
+	}
+}
+
+/* Picks a button from a submenu per category */
+fn button_menu(button: &mut Buttons, ui: &mut egui::Ui) {
+	let name: &'static str = (*button).into();
+	ui.menu_button(button.label().as_ref(), |ui| {
+		for category in ButtonCategory::ALL {
+			ui.menu_button(category.title(), |ui| {
+				egui::ScrollArea::vertical()
+					.max_height(300.0)
+					.show(ui, |ui| {
+						for button_variant in category.buttons() {
+							if ui
+								.selectable_label(
+									*button == button_variant,
+									button_variant.label().as_ref(),
+								)
+								.clicked()
+							{
+								*button = button_variant;
+								ui.close_menu();
+							}
+						}
+					});
+			});
+		}
+	})
+	.response
+	.on_hover_text(name);
+}
+
+fn compatibility_badge(compat: &Compatibility) -> &'static str {
+	match compat {
+		Compatibility::Compatible => "compatible",
+		Compatibility::Incompatible(_) => "incompatible",
+		Compatibility::Unknown(_) => "unknown",
+	}
+}
+
+/* Which game and launcher the patch is for, and whether this is them */
+fn patch_target(
+	patch: &mut Patch,
+	compat: &Compatibility,
+	game_info: Option<&GameInfo>,
+	ui: &mut egui::Ui,
+) {
+	let optional = |value: Option<String>| value.unwrap_or_else(|| "any".to_string());
+	egui::Grid::new(ui.id().with("patch_target")).show(ui, |ui| {
+		ui.label("Game SHA-1");
+		ui.label(optional(patch.game_sha1.as_ref().map(ExeHash::to_string)));
+		ui.end_row();
+		ui.label("Game version");
+		ui.label(optional(
+			patch.game_version.as_ref().map(Version::to_string),
+		));
+		ui.end_row();
+		ui.label("Launcher version");
+		ui.label(match &patch.min_launcher_version {
+			Some(version) => format!("{} or newer", version),
+			None => "any".to_string(),
+		});
+		ui.end_row();
+	});
 	ui.horizontal(|ui| {
-		ui.label(label);
-		ui.checkbox(value, "");
+		if let Some(game_info) = game_info {
+			if ui
+				.button("Target this game")
+				.on_hover_text(format!(
+					"Only {} with the same hash and version is compatible",
+					GAME_EXE
+				))
+				.clicked()
+			{
+				patch.game_sha1 = Some(game_info.hash.clone());
+				patch.game_version = game_info.version.clone();
+			}
+		}
+		if ui
+			.button("Require this launcher")
+			.on_hover_text("Older launchers mark the patch incompatible")
+			.clicked()
+		{
+			patch.min_launcher_version = Some(Version::launcher());
+		}
+		if ui.button("Clear target").clicked() {
+			patch.game_sha1 = None;
+			patch.game_version = None;
+			patch.min_launcher_version = None;
+		}
 	});
+	match compat {
+		Compatibility::Compatible => {
+			ui.colored_label(egui::Color32::LIGHT_GREEN, "Compatible with this game");
+		}
+		Compatibility::Incompatible(reason) => {
+			ui.colored_label(
+				egui::Color32::LIGHT_RED,
+				format!("Incompatible, {}", reason),
+			);
+		}
+		Compatibility::Unknown(reason) => {
+			ui.label(format!("Compatibility unknown, {}", reason));
+		}
+	}
 }
 
-fn vec2_x_modify(size: &mut egui::Vec2, modify: f32) -> egui::Vec2 {
-	size.x /= modify;
-	*size
+/* Either a fixed address or a signature that is looked up in the executable */
+fn address_editor(
+	internal_patch: &mut InternalPatch,
+	game_exe: &Option<Result<PeImage, PeError>>,
+	ui: &mut egui::Ui,
+) {
+	let mut by_signature = internal_patch.signature.is_some();
+	if ui
+		.checkbox(&mut by_signature, "Find by signature")
+		.on_hover_text(
+			"Look the address up by the bytes around it, so the patch survives game updates",
+		)
+		.changed()
+	{
+		internal_patch.signature = by_signature.then(AddressSignature::default);
+	}
+	let signature = match &mut internal_patch.signature {
+		Some(signature) => signature,
+		None => {
+			ui.horizontal(|ui| {
+				ui.label("Address");
+				hex_text_box(&mut internal_patch.address, 0, 4.0, ui);
+			});
+			return;
+		}
+	};
+	let before = (signature.pattern.clone(), signature.offset);
+	ui.horizontal(|ui| {
+		ui.label("Signature")
+			.on_hover_text("Hex bytes separated by spaces, ?? matches any byte");
+		ui.add_sized(
+			vec2_x_modify(&mut ui.available_size(), 4.0),
+			egui::TextEdit::singleline(&mut signature.pattern).hint_text("48 8B ?? 24 08"),
+		);
+	});
+	ui.horizontal(|ui| {
+		ui.label("Offset")
+			.on_hover_text("Added to where the signature starts");
+		hex_text_box(&mut signature.offset, 0, 4.0, ui);
+	});
+	match &signature.resolved {
+		Some(Ok(address)) => {
+			ui.label(format!("Resolved to {:#X}", address));
+		}
+		Some(Err(err)) => {
+			ui.colored_label(egui::Color32::LIGHT_RED, err.to_string());
+		}
+		None => (),
+	}
+	if before != (signature.pattern.clone(), signature.offset) {
+		if let Some(image) = game_exe {
+			internal_patch.resolve_signature(image.as_ref());
+		}
+	}
 }
 
-fn read_toml_integer_array_to_vec(vec: &mut Vec<i64>, array: &toml_edit::Item) {
-	for value in array.as_array().unwrap() {
-		vec.push(value.as_integer().unwrap());
+/* What the patch entry replaces next to what it writes, and the bytes it expects to replace */
+fn original_bytes(
+	internal_patch: &mut InternalPatch,
+	game_exe: &Option<Result<PeImage, PeError>>,
+	ui: &mut egui::Ui,
+) {
+	let comparison = match game_exe {
+		Some(Ok(image)) => Some(pe::compare(image, internal_patch)),
+		/* Without the executable only the data can be checked */
+		_ => pe::check_data(internal_patch).err().map(Err),
+	};
+	match &comparison {
+		Some(Ok(comparison)) => {
+			egui::Grid::new(ui.id().with("original_bytes")).show(ui, |ui| {
+				ui.label("Original");
+				ui.label("Patched");
+				ui.end_row();
+				ui.monospace(hex_bytes(&comparison.original));
+				ui.monospace(hex_bytes(&comparison.patched));
+				ui.end_row();
+			});
+		}
+		Some(Err(err)) => {
+			ui.colored_label(egui::Color32::YELLOW, err.to_string());
+		}
+		None => (),
+	}
+	let current = match &comparison {
+		Some(Ok(comparison)) => Some(&comparison.original),
+		_ => None,
+	};
+	ui.horizontal(|ui| {
+		ui.label("Expected original")
+			.on_hover_text("Bytes the patch is made for, checked against the executable");
+		match &internal_patch.original {
+			Some(original) => ui.monospace(hex_bytes(original)),
+			None => ui.label("any"),
+		};
+		if ui
+			.add_enabled(
+				current.is_some() && current != internal_patch.original.as_ref(),
+				egui::Button::new("Use current"),
+			)
+			.clicked()
+		{
+			internal_patch.original = current.cloned();
+		}
+		if ui
+			.add_enabled(
+				internal_patch.original.is_some(),
+				egui::Button::new("Clear"),
+			)
+			.clicked()
+		{
+			internal_patch.original = None;
+		}
+	});
+	if let (Some(Ok(comparison)), Some(original)) = (&comparison, &internal_patch.original) {
+		if comparison.mismatch {
+			ui.colored_label(
+				egui::Color32::LIGHT_RED,
+				format!(
+					"{} has {} instead of {}, the patch may be for another version of the game",
+					GAME_EXE,
+					hex_bytes(&comparison.original),
+					hex_bytes(original)
+				),
+			);
+		}
 	}
 }
 
-fn read_toml_array_to_vec<T>(vec: &mut Vec<T>, array: &toml_edit::Item)
-where
-	T: FromStr,
-	<T as FromStr>::Err: std::fmt::Debug,
-{
-	for value in array.as_array().unwrap() {
-		vec.push(T::from_str(value.as_str().unwrap()).unwrap());
-	}
+/* Greyed out while the value is already the default */
+fn reset_button(is_default: bool, hover: &str, ui: &mut egui::Ui) -> bool {
+	ui.add_enabled(!is_default, egui::Button::new("Default").small())
+		.on_hover_text(hover)
+		.on_disabled_hover_text(hover)
+		.clicked()
 }
 
-#[allow(unused_assignments, unused_variables)]
-fn read_vec_to_toml_array<T>(vec: &Vec<T>, array: &mut toml_edit::Item)
-where
-	T: FromStr,
-	<T as FromStr>::Err: std::fmt::Debug,
-	toml_edit::Value: From<T>,
-	T: Clone,
-{
-	let diff = vec.len() - array.as_array().unwrap().len();
-	for _ in 0..diff {
-		array
-			.as_array_mut()
-			.unwrap()
-			.push(toml_edit::Array::default());
+/* The last rejected input, or what is wrong with the value from the file */
+fn config_error_label(
+	config_error: &Option<(&'static str, String)>,
+	loaded_errors: &[ConfigError],
+	key: &str,
+	ui: &mut egui::Ui,
+) {
+	if let Some((_, message)) = config_error.as_ref().filter(|(other, _)| *other == key) {
+		ui.colored_label(egui::Color32::YELLOW, message);
+	} else if let Some(error) = loaded_errors.iter().find(|error| error.key == key) {
+		ui.colored_label(egui::Color32::LIGHT_RED, &error.message);
 	}
-	for (i, value) in array.as_array_mut().unwrap().iter_mut().enumerate() {
-		*value = toml_edit::value(vec[i].clone()).into_value().unwrap();
+}
+
+/* Editor for config.toml keys the launcher has no field for, picked by value type */
+fn config_value_editor(value: &mut ConfigValue, ui: &mut egui::Ui) {
+	match value {
+		ConfigValue::Bool(value) => {
+			ui.checkbox(value, "");
+		}
+		ConfigValue::Integer(value) => {
+			if let Some(Ok(entered)) = int_text_box(&*value, 4.0, ui) {
+				*value = entered;
+			}
+		}
+		ConfigValue::Float(value) => {
+			ui.add(egui::DragValue::new(value).speed(0.1));
+		}
+		ConfigValue::String(value) => {
+			ui.text_edit_singleline(value);
+		}
+		ConfigValue::Array(values) => {
+			ui.vertical(|ui| {
+				let mut edit = None;
+				let count = values.len();
+				for (i, element) in values.iter_mut().enumerate() {
+					ui.horizontal(|ui| {
+						config_value_editor(element, ui);
+						if let Some(list_edit) = list_edit_buttons(i, count, true, ui) {
+							edit = Some(list_edit);
+						}
+					});
+				}
+				if let Some(edit) = edit {
+					edit.apply(values);
+				}
+				/* New elements take the type of the last one */
+				match values.last().map(ConfigValue::empty_like) {
+					Some(element) => {
+						if ui.add(egui::Button::new("+")).clicked() {
+							values.push(element);
+						}
+					}
+					None => {
+						ui.horizontal(|ui| {
+							ui.weak("Empty list");
+							ui.menu_button("+", |ui| {
+								for element in &EMPTY_VALUES {
+									if ui.button(element.type_name()).clicked() {
+										values.push(element.clone());
+										ui.close_menu();
+									}
+								}
+							});
+						});
+					}
+				}
+			});
+		}
 	}
 }
 
-fn buttons_vec_to_string(vec: &Vec<Buttons>) -> Vec<String> {
-	let mut ret: Vec<String> = vec![];
+fn buttons_label(buttons: &[Buttons]) -> String {
+	if buttons.is_empty() {
+		"none".to_string()
+	} else {
+		buttons
+			.iter()
+			.map(|button| button.label())
+			.collect::<Vec<_>>()
+			.join(", ")
+	}
+}
 
-	for button in vec {
-		let button_str: &'static str = button.into();
-		ret.push(button_str.to_string());
+fn conflict_label(label: String, conflicted: bool) -> egui::RichText {
+	if conflicted {
+		egui::RichText::new(label).color(egui::Color32::LIGHT_RED)
+	} else {
+		egui::RichText::new(label)
 	}
+}
+
+fn simple_checkbox(label: &str, value: &mut bool, ui: &mut egui::Ui) {
+	ui.horizontal(|ui| {
+		ui.label(label);
+		ui.checkbox(value, "");
+	});
+}
 
-	ret
+fn vec2_x_modify(size: &mut egui::Vec2, modify: f32) -> egui::Vec2 {
+	size.x /= modify;
+	*size
 }
@@ -0,0 +1,1115 @@
+use crate::{
+	actions::{find_action, group_of, ActionGroup, ACTIONS},
+	backup::write_atomic,
+	compat::{ExeHash, Version},
+	pe::{PeError, PeImage},
+	problems::{has_problems, read_document, Problem, ProblemKind, ProblemReader},
+	schema::{find_key, key_path, ConfigValue, SchemaDefault, CONFIG_SCHEMA},
+	signature::{resolve_signatures, AddressSignature},
+};
+use std::{path::Path, str::FromStr};
+use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, EnumString, EnumIter, Hash, IntoStaticStr)]
+pub enum Buttons {
+	F1,
+	F2,
+	F3,
+	F4,
+	F5,
+	F6,
+	F7,
+	F8,
+	F9,
+	F10,
+	F11,
+	F12,
+	NUM0,
+	NUM1,
+	NUM2,
+	NUM3,
+	NUM4,
+	NUM5,
+	NUM6,
+	NUM7,
+	NUM8,
+	NUM9,
+	Q,
+	W,
+	E,
+	R,
+	T,
+	Y,
+	U,
+	I,
+	O,
+	P,
+	A,
+	S,
+	D,
+	F,
+	G,
+	H,
+	J,
+	K,
+	L,
+	Z,
+	X,
+	C,
+	V,
+	B,
+	N,
+	M,
+	UPARROW,
+	LEFTARROW,
+	DOWNARROW,
+	RIGHTARROW,
+	ENTER,
+	SPACE,
+	CONTROL,
+	SHIFT,
+	TAB,
+	ALT,
+	ESCAPE,
+	BACKSPACE,
+	INSERT,
+	DELETE,
+	HOME,
+	END,
+	PAGEUP,
+	PAGEDOWN,
+	CAPSLOCK,
+	MINUS,
+	EQUALS,
+	LEFTBRACKET,
+	RIGHTBRACKET,
+	BACKSLASH,
+	SEMICOLON,
+	APOSTROPHE,
+	COMMA,
+	PERIOD,
+	SLASH,
+	GRAVE,
+	NUMPAD0,
+	NUMPAD1,
+	NUMPAD2,
+	NUMPAD3,
+	NUMPAD4,
+	NUMPAD5,
+	NUMPAD6,
+	NUMPAD7,
+	NUMPAD8,
+	NUMPAD9,
+	MULTIPLY,
+	ADD,
+	SUBTRACT,
+	DECIMAL,
+	DIVIDE,
+	MOUSE_LEFT,
+	MOUSE_RIGHT,
+	MOUSE_MIDDLE,
+	MOUSE_X1,
+	MOUSE_X2,
+	MOUSE_SCROLL_UP,
+	MOUSE_SCROLL_DOWN,
+	SDL_A,
+	SDL_B,
+	SDL_X,
+	SDL_Y,
+	SDL_BACK,
+	SDL_GUIDE,
+	SDL_START,
+	SDL_LSHOULDER,
+	SDL_LTRIGGER,
+	SDL_RSHOULDER,
+	SDL_RTRIGGER,
+	SDL_DPAD_UP,
+	SDL_DPAD_LEFT,
+	SDL_DPAD_DOWN,
+	SDL_DPAD_RIGHT,
+	SDL_MISC,
+	SDL_PADDLE1,
+	SDL_PADDLE2,
+	SDL_PADDLE3,
+	SDL_PADDLE4,
+	SDL_TOUCHPAD,
+	SDL_LSTICK_UP,
+	SDL_LSTICK_LEFT,
+	SDL_LSTICK_RIGHT,
+	SDL_LSTICK_DOWN,
+	SDL_LSTICK_PRESS,
+	SDL_RSTICK_UP,
+	SDL_RSTICK_LEFT,
+	SDL_RSTICK_RIGHT,
+	SDL_RSTICK_DOWN,
+	SDL_RSTICK_PRESS,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct AllConfig {
+	pub config: Config,
+	pub keyconfig: Keyconfig,
+	pub patches: Vec<Patch>,
+	pub translations: Vec<Translation>,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Config {
+	pub fps: i32,
+	pub internal_res_x: i32,
+	pub internal_res_y: i32,
+	pub fullscreen: bool,
+	pub rumble_intensity: i32,
+	/* Keys not in CONFIG_SCHEMA by the parts of their path, in file order. Parts are kept
+	 * apart because a quoted key like "a.b" has a dot in it */
+	pub other: Vec<(Vec<String>, ConfigValue)>,
+}
+
+/* Bindings by keyconfig.toml key, known actions first in ACTIONS order
+ * followed by any unknown keys in file order */
+#[derive(Debug, PartialEq, Clone)]
+pub struct Keyconfig {
+	pub bindings: Vec<(String, Vec<Buttons>)>,
+}
+
+impl Default for Keyconfig {
+	fn default() -> Self {
+		Keyconfig {
+			bindings: ACTIONS
+				.iter()
+				.map(|action| (action.key.to_string(), vec![]))
+				.collect(),
+		}
+	}
+}
+
+impl Keyconfig {
+	pub fn actions(&self) -> impl Iterator<Item = (&str, &Vec<Buttons>)> {
+		self.bindings.iter().map(|(key, vec)| (key.as_str(), vec))
+	}
+
+	pub fn actions_mut(&mut self) -> impl Iterator<Item = (&str, &mut Vec<Buttons>)> {
+		self.bindings
+			.iter_mut()
+			.map(|(key, vec)| (key.as_str(), vec))
+	}
+
+	pub fn group(&self, group: ActionGroup) -> impl Iterator<Item = (&str, &Vec<Buttons>)> {
+		self.actions()
+			.filter(move |(key, _)| group_of(key) == group)
+	}
+
+	pub fn get(&self, key: &str) -> Option<&Vec<Buttons>> {
+		self.actions()
+			.find(|(other, _)| *other == key)
+			.map(|(_, vec)| vec)
+	}
+
+	pub fn get_mut(&mut self, key: &str) -> Option<&mut Vec<Buttons>> {
+		self.actions_mut()
+			.find(|(other, _)| *other == key)
+			.map(|(_, vec)| vec)
+	}
+
+	/* Unknown keys are added to the end */
+	pub fn set(&mut self, key: &str, buttons: Vec<Buttons>) {
+		match self.get_mut(key) {
+			Some(vec) => *vec = buttons,
+			None => self.bindings.push((key.to_string(), buttons)),
+		}
+	}
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Patch {
+	pub name: String,
+	pub author: String,
+	/* Optional, empty when the file doesn't have them */
+	pub version: String,
+	pub description: String,
+	pub url: String,
+	/* What the patch was made for, checked against the local executable */
+	pub game_sha1: Option<ExeHash>,
+	pub game_version: Option<Version>,
+	pub min_launcher_version: Option<Version>,
+	pub enabled: bool,
+	pub patches: Vec<InternalPatch>,
+	pub file: String,
+}
+
+impl Patch {
+	/* Not written until the next save */
+	pub fn new(stem: &str) -> Patch {
+		Patch {
+			name: stem.to_string(),
+			file: patch_file(stem),
+			..Default::default()
+		}
+	}
+}
+
+pub fn patch_file(stem: &str) -> String {
+	format!("patches/{}.toml", stem)
+}
+
+pub fn file_stem(file: &str) -> String {
+	Path::new(file)
+		.file_stem()
+		.map(|stem| stem.to_string_lossy().to_string())
+		.unwrap_or_default()
+}
+
+/* Names end up as file names so keep them to something every filesystem accepts */
+pub fn valid_file_stem(name: &str) -> bool {
+	!name.trim().is_empty()
+		&& name == name.trim()
+		&& name
+			.chars()
+			.all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+}
+
+/* Sorted names of the .toml files in a folder, empty when it does not exist */
+pub fn toml_stems(dir: &Path) -> Vec<String> {
+	let mut names: Vec<String> = std::fs::read_dir(dir)
+		.map(|entries| {
+			entries
+				.filter_map(|entry| entry.ok())
+				.map(|entry| entry.path())
+				.filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+				.filter_map(|path| {
+					path.file_stem()
+						.map(|stem| stem.to_string_lossy().to_string())
+				})
+				.filter(|name| valid_file_stem(name))
+				.collect()
+		})
+		.unwrap_or_default();
+	names.sort();
+	names
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, EnumString, EnumIter, IntoStaticStr)]
+pub enum DataTypes {
+	i8,
+	i8_arr,
+	u8,
+	#[default]
+	u8_arr,
+	i16,
+	i16_arr,
+	u16,
+	u16_arr,
+	i32,
+	i32_arr,
+	u32,
+	u32_arr,
+	i64,
+	i64_arr,
+	string,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct InternalPatch {
+	pub address: i64,
+	pub data_type: DataTypes,
+	pub data_int: i64,
+	pub data_int_arr: Vec<i64>,
+	pub data_string: String,
+	/* Bytes the patch expects to replace in the executable */
+	pub original: Option<Vec<u8>>,
+	/* Written instead of the address, which then comes from the executable */
+	pub signature: Option<AddressSignature>,
+	/* The [[patch]] table the entry was read from and is written back into, None for new
+	 * entries. Entries that fail to read are skipped so it can be past the entry's position */
+	pub table: Option<usize>,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Translation {
+	pub language: String,
+	pub author: String,
+	pub enabled: bool,
+	pub translations: Vec<InternalTranslation>,
+	pub file: String,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, EnumString, EnumIter, IntoStaticStr)]
+pub enum SubGameStates {
+	SUB_DATA_INITIALIZE,
+	SUB_SYSTEM_STARTUP,
+	SUB_SYSTEM_STARTUP_ERROR,
+	SUB_WARNING,
+	SUB_LOGO,
+	SUB_RATING,
+	SUB_DEMO,
+	SUB_TITLE,
+	SUB_RANKING,
+	SUB_SCORE_RANKING,
+	SUB_CM,
+	SUB_PHOTO_MODE_DEMO,
+	SUB_SELECTOR,
+	SUB_GAME_MAIN,
+	SUB_GAME_SEL,
+	SUB_STAGE_RESULT,
+	SUB_SCREEN_SHOT_SEL,
+	SUB_SCREEN_SHOT_RESULT,
+	SUB_GAME_OVER,
+	SUB_DATA_TEST_MAIN,
+	SUB_DATA_TEST_MISC,
+	SUB_DATA_TEST_OBJ,
+	SUB_DATA_TEST_STG,
+	SUB_DATA_TEST_MOT,
+	SUB_DATA_TEST_COLLISION,
+	SUB_DATA_TEST_SPR,
+	SUB_DATA_TEST_AET,
+	SUB_DATA_TEST_AUTH_3D,
+	SUB_DATA_TEST_CHR,
+	SUB_DATA_TEST_ITEM,
+	SUB_DATA_TEST_PERF,
+	SUB_DATA_TEST_PVSCRIPT,
+	SUB_DATA_TEST_PRINT,
+	SUB_DATA_TEST_CARD,
+	SUB_DATA_TEST_OPD,
+	SUB_DATA_TEST_SLIDER,
+	SUB_DATA_TEST_GLITTER,
+	SUB_DATA_TEST_GRAPHICS,
+	SUB_DATA_TEST_COLLECTION_CARD,
+	SUB_TEST_MODE_MAIN,
+	SUB_APP_ERROR,
+	#[default]
+	SUB_MAX,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct InternalTranslation {
+	pub old: String,
+	pub new: String,
+	pub state: SubGameStates,
+}
+impl AllConfig {
+	/* Entries with a signature have no address until they are resolved, see load_with_image */
+	pub fn load(dir: &Path) -> (AllConfig, Vec<Problem>) {
+		let mut problems = vec![];
+		let mut all = AllConfig::default();
+
+		if let Some(doc) = read_document(dir, "config.toml", &mut problems) {
+			let mut reader = ProblemReader::new("config.toml", &mut problems);
+			all.config = read_config(doc.as_item(), &mut reader);
+			for error in all.config.validate() {
+				/* A key that did not read has already been reported */
+				if !problems
+					.iter()
+					.any(|problem| problem.file == "config.toml" && problem.key == error.key)
+				{
+					problems.push(Problem {
+						file: "config.toml".to_string(),
+						key: error.key.to_string(),
+						line_col: None,
+						kind: ProblemKind::OutOfRange(error.message),
+					});
+				}
+			}
+		}
+
+		if let Some(doc) = read_document(dir, "keyconfig.toml", &mut problems) {
+			let mut reader = ProblemReader::new("keyconfig.toml", &mut problems);
+			all.keyconfig = read_keyconfig(doc.as_item(), &mut reader);
+		}
+
+		for file in read_dir_files(dir, "patches", &mut problems) {
+			if let Some(doc) = read_document(dir, &file, &mut problems) {
+				let mut reader = ProblemReader::new(&file, &mut problems);
+				all.patches
+					.push(read_patch(doc.as_item(), &file, &mut reader));
+			}
+		}
+
+		if dir.join("lang.dll").exists() {
+			for file in read_dir_files(dir, "translations", &mut problems) {
+				if let Some(doc) = read_document(dir, &file, &mut problems) {
+					let mut reader = ProblemReader::new(&file, &mut problems);
+					all.translations
+						.push(read_translation(doc.as_item(), &file, &mut reader));
+				}
+			}
+		}
+
+		(all, problems)
+	}
+
+	/* Scanning the executable for signatures is slow, so the image is read once by the caller
+	 * and only when addresses are needed */
+	pub fn load_with_image(
+		dir: &Path,
+		image: Result<&PeImage, &PeError>,
+	) -> (AllConfig, Vec<Problem>) {
+		let (mut all, mut problems) = AllConfig::load(dir);
+		problems.extend(resolve_signatures(&mut all.patches, image));
+		(all, problems)
+	}
+
+	/* Writes config.toml and keyconfig.toml from the defaults when they are missing,
+	 * returns the files that were created */
+	pub fn create_missing(dir: &Path) -> Result<Vec<&'static str>, Vec<Problem>> {
+		let mut created = vec![];
+		let mut problems = vec![];
+		if !dir.join("config.toml").exists() {
+			create_document(dir, "config.toml", &mut problems, |doc| {
+				write_config(&Config::game_defaults(), doc.as_item_mut())
+			});
+			created.push("config.toml");
+		}
+		if !dir.join("keyconfig.toml").exists() {
+			create_document(dir, "keyconfig.toml", &mut problems, |doc| {
+				write_keyconfig(Keyconfig::game_defaults(), doc.as_item_mut())
+			});
+			created.push("keyconfig.toml");
+		}
+		if problems.is_empty() {
+			Ok(created)
+		} else {
+			Err(problems)
+		}
+	}
+
+	/* Files that do not load cleanly are left untouched */
+	pub fn save(&mut self, dir: &Path) -> Result<(), Vec<Problem>> {
+		let mut problems = vec![];
+
+		save_document(
+			dir,
+			"config.toml",
+			&mut problems,
+			|doc, reader| {
+				read_config(doc, reader);
+			},
+			|doc| write_config(&self.config, doc.as_item_mut()),
+		);
+		save_document(
+			dir,
+			"keyconfig.toml",
+			&mut problems,
+			|doc, reader| {
+				read_keyconfig(doc, reader);
+			},
+			|doc| write_keyconfig(&self.keyconfig, doc.as_item_mut()),
+		);
+		for patch in &mut self.patches {
+			let before = problems.len();
+			if !dir.join(&patch.file).exists() {
+				create_document(dir, &patch.file, &mut problems, |doc| {
+					write_patch(patch, doc)
+				});
+			} else {
+				save_document(
+					dir,
+					&patch.file,
+					&mut problems,
+					|doc, reader| {
+						read_patch(doc, &patch.file, reader);
+					},
+					|doc| write_patch(patch, doc),
+				);
+			}
+			/* The file now has one table per entry, in order */
+			if problems.len() == before {
+				for (i, entry) in patch.patches.iter_mut().enumerate() {
+					entry.table = Some(i);
+				}
+			}
+		}
+		for translation in &self.translations {
+			save_document(
+				dir,
+				&translation.file,
+				&mut problems,
+				|doc, reader| {
+					read_translation(doc, &translation.file, reader);
+				},
+				|doc| write_translation(translation, doc),
+			);
+		}
+
+		if problems.is_empty() {
+			Ok(())
+		} else {
+			Err(problems)
+		}
+	}
+}
+
+/* Missing keys keep their default */
+pub(crate) fn read_config(doc: &toml_edit::Item, reader: &mut ProblemReader) -> Config {
+	let mut config = Config::game_defaults();
+	for key in CONFIG_SCHEMA {
+		match key.default {
+			SchemaDefault::Integer(default) => {
+				if !reader.filled_in(doc, key.key, default) {
+					let value = reader.i32(doc, key.key).unwrap_or_default();
+					if let Some(field) = config.int_mut(key.key) {
+						*field = value;
+					}
+				}
+			}
+			SchemaDefault::Bool(default) => {
+				if !reader.filled_in(doc, key.key, default) {
+					let value = reader.bool(doc, key.key).unwrap_or_default();
+					if let Some(field) = config.bool_mut(key.key) {
+						*field = value;
+					}
+				}
+			}
+		}
+	}
+	config.other = read_other(doc, &[]);
+	config
+}
+
+/* Dates and arrays of tables are left alone, there is no editor for them */
+fn read_other(item: &toml_edit::Item, prefix: &[String]) -> Vec<(Vec<String>, ConfigValue)> {
+	let mut other = vec![];
+	let table = match item.as_table_like() {
+		Some(table) => table,
+		None => return other,
+	};
+	for (key, child) in table.iter() {
+		let mut path = prefix.to_vec();
+		path.push(key.to_string());
+		if child.is_table_like() {
+			other.extend(read_other(child, &path));
+		} else if find_key(&key_path(&path)).is_none() {
+			if let Some(value) = child.as_value().and_then(ConfigValue::from_toml) {
+				other.push((path, value));
+			}
+		}
+	}
+	other
+}
+
+pub(crate) fn write_config(config: &Config, doc: &mut toml_edit::Item) {
+	write_value(&mut doc["fps"], config.fps as i64);
+	write_value(&mut doc["internalRes"]["x"], config.internal_res_x as i64);
+	write_value(&mut doc["internalRes"]["y"], config.internal_res_y as i64);
+	write_value(&mut doc["fullscreen"], config.fullscreen);
+	write_value(&mut doc["rumbleIntensity"], config.rumble_intensity as i64);
+	for (path, value) in &config.other {
+		let item = path
+			.iter()
+			.fold(&mut *doc, |item, part| &mut item[part.as_str()]);
+		match value {
+			ConfigValue::Array(values) => {
+				write_array(item, values.iter().map(ConfigValue::to_toml).collect())
+			}
+			_ => write_value(item, value.to_toml()),
+		}
+	}
+}
+
+/* Missing actions get their default bindings */
+pub(crate) fn read_keyconfig(doc: &toml_edit::Item, reader: &mut ProblemReader) -> Keyconfig {
+	read_bindings(doc, reader, Some(Keyconfig::game_defaults()))
+}
+
+/* The defaults themselves are read without any */
+pub(crate) fn read_bindings(
+	doc: &toml_edit::Item,
+	reader: &mut ProblemReader,
+	defaults: Option<&Keyconfig>,
+) -> Keyconfig {
+	let mut keyconfig = Keyconfig::default();
+	for action in ACTIONS {
+		if let Some(default) = defaults.and_then(|defaults| defaults.get(action.key)) {
+			let names: Vec<&'static str> = default.iter().map(|button| button.into()).collect();
+			if reader.filled_in(doc, action.key, names.join(",")) {
+				keyconfig.set(action.key, default.clone());
+				continue;
+			}
+		}
+		if let Some(parsed) = reader.parsed_array(doc, action.key) {
+			keyconfig.set(action.key, parsed);
+		}
+	}
+	/* Actions added on the DLL side stay editable, anything that isn't a list is left alone */
+	let unknown: Vec<String> = doc
+		.as_table_like()
+		.map(|table| {
+			table
+				.iter()
+				.filter(|(key, item)| find_action(key).is_none() && item.is_array())
+				.map(|(key, _)| key.to_string())
+				.collect()
+		})
+		.unwrap_or_default();
+	for key in unknown {
+		let parsed = reader.parsed_array(doc, &key).unwrap_or_default();
+		keyconfig.set(&key, parsed);
+	}
+	keyconfig
+}
+
+pub(crate) fn write_keyconfig(keyconfig: &Keyconfig, doc: &mut toml_edit::Item) {
+	for (name, vec) in keyconfig.actions() {
+		write_array(&mut doc[name], buttons_to_values(vec));
+	}
+}
+
+fn read_patch(doc: &toml_edit::Item, file: &str, reader: &mut ProblemReader) -> Patch {
+	let mut patch_data = Patch {
+		name: reader.str(doc, "name").unwrap_or(file).to_string(),
+		author: reader.str(doc, "author").unwrap_or_default().to_string(),
+		version: optional_str(doc, "version", reader),
+		description: optional_str(doc, "description", reader),
+		url: optional_str(doc, "url", reader),
+		game_sha1: optional_parse(doc, "game_sha1", reader),
+		game_version: optional_parse(doc, "game_version", reader),
+		min_launcher_version: optional_parse(doc, "min_launcher_version", reader),
+		enabled: reader.bool(doc, "enabled").unwrap_or_default(),
+		patches: vec![],
+		file: file.to_string(),
+	};
+
+	/* A new file has no entries yet */
+	if doc.get("patch").is_none() {
+		return patch_data;
+	}
+	let tables = match reader.array_of_tables(doc, "patch") {
+		Some(tables) => tables,
+		None => return patch_data,
+	};
+	for i in 0..tables.as_array_of_tables().unwrap().len() {
+		let table = &tables[i];
+		let mut reader = reader.scoped(&format!("patch[{}]", i));
+		let signature = if table.get("signature").is_some() {
+			let pattern = reader.str(table, "signature").unwrap_or_default();
+			let offset = match table.get("offset") {
+				Some(_) => reader.integer(table, "offset").unwrap_or_default(),
+				None => 0,
+			};
+			Some(AddressSignature::new(pattern, offset))
+		} else {
+			None
+		};
+		let address = match signature {
+			Some(_) => Some(0),
+			None => reader.integer(table, "address"),
+		};
+		let data_type = reader.parse::<DataTypes>(table, "data_type");
+		let (address, data_type) = match (address, data_type) {
+			(Some(address), Some(data_type)) => (address, data_type),
+			_ => continue,
+		};
+		let mut patch = InternalPatch {
+			address,
+			data_type,
+			signature,
+			table: Some(i),
+			..Default::default()
+		};
+		match data_type {
+			DataTypes::i8
+			| DataTypes::u8
+			| DataTypes::i16
+			| DataTypes::u16
+			| DataTypes::i32
+			| DataTypes::u32
+			| DataTypes::i64 => patch.data_int = reader.integer(table, "data").unwrap_or_default(),
+			DataTypes::i8_arr
+			| DataTypes::u8_arr
+			| DataTypes::i16_arr
+			| DataTypes::u16_arr
+			| DataTypes::i32_arr
+			| DataTypes::u32_arr
+			| DataTypes::i64_arr => {
+				patch.data_int_arr = reader.integer_array(table, "data").unwrap_or_default()
+			}
+			DataTypes::string => {
+				patch.data_string = reader.str(table, "data").unwrap_or_default().to_string()
+			}
+		};
+		if table.get("original").is_some() {
+			patch.original = reader.byte_array(table, "original");
+		}
+		patch_data.patches.push(patch);
+	}
+	patch_data
+}
+
+fn write_patch(patch_data: &Patch, doc: &mut toml_edit::Document) {
+	write_value(&mut doc["name"], patch_data.name.as_str());
+	write_value(&mut doc["author"], patch_data.author.as_str());
+	write_optional(
+		doc,
+		"version",
+		Some(&patch_data.version).filter(|s| !s.is_empty()),
+	);
+	write_optional(
+		doc,
+		"description",
+		Some(&patch_data.description).filter(|s| !s.is_empty()),
+	);
+	write_optional(doc, "url", Some(&patch_data.url).filter(|s| !s.is_empty()));
+	write_optional(doc, "game_sha1", patch_data.game_sha1.as_ref());
+	write_optional(doc, "game_version", patch_data.game_version.as_ref());
+	write_optional(
+		doc,
+		"min_launcher_version",
+		patch_data.min_launcher_version.as_ref(),
+	);
+	write_value(&mut doc["enabled"], patch_data.enabled);
+
+	let mut tables = take_tables(
+		&mut doc["patch"],
+		patch_data.patches.iter().map(|patch| patch.table),
+	);
+	for (table, patch) in tables.iter_mut().zip(&patch_data.patches) {
+		let moved = !table.contains_key(match patch.signature {
+			Some(_) => "signature",
+			None => "address",
+		});
+		match &patch.signature {
+			Some(signature) => {
+				table.remove("address");
+				write_value(&mut table["signature"], signature.pattern.as_str());
+				if signature.offset != 0 || table.contains_key("offset") {
+					write_value(&mut table["offset"], signature.offset);
+				}
+			}
+			None => {
+				table.remove("signature");
+				table.remove("offset");
+				write_value(&mut table["address"], hex_address(patch.address));
+			}
+		}
+		/* Keep where the entry writes at the top when switching between address and signature */
+		if moved {
+			table.sort_values_by(|a, _, b, _| address_rank(a.get()).cmp(&address_rank(b.get())));
+		}
+		let data_type_str: &'static str = patch.data_type.into();
+		write_value(&mut table["data_type"], data_type_str);
+		match patch.data_type {
+			DataTypes::i8
+			| DataTypes::u8
+			| DataTypes::i16
+			| DataTypes::u16
+			| DataTypes::i32
+			| DataTypes::u32
+			| DataTypes::i64 => write_value(&mut table["data"], patch.data_int),
+			DataTypes::i8_arr
+			| DataTypes::u8_arr
+			| DataTypes::i16_arr
+			| DataTypes::u16_arr
+			| DataTypes::i32_arr
+			| DataTypes::u32_arr
+			| DataTypes::i64_arr => write_array(
+				&mut table["data"],
+				patch.data_int_arr.iter().map(|&data| data.into()).collect(),
+			),
+			DataTypes::string => write_value(&mut table["data"], patch.data_string.as_str()),
+		};
+		match &patch.original {
+			Some(original) => write_array(
+				&mut table["original"],
+				original.iter().map(|&byte| hex_byte(byte)).collect(),
+			),
+			None => {
+				table.remove("original");
+			}
+		}
+	}
+	doc["patch"] = toml_edit::Item::ArrayOfTables(tables.into_iter().collect());
+}
+
+/* Keys a file can leave out */
+fn optional_str(doc: &toml_edit::Item, key: &str, reader: &mut ProblemReader) -> String {
+	match doc.get(key) {
+		Some(_) => reader.str(doc, key).unwrap_or_default().to_string(),
+		None => String::new(),
+	}
+}
+
+fn optional_parse<T: FromStr>(
+	doc: &toml_edit::Item,
+	key: &str,
+	reader: &mut ProblemReader,
+) -> Option<T> {
+	doc.get(key).and_then(|_| reader.parse(doc, key))
+}
+
+/* Written as a string, removed when there is nothing to write */
+fn write_optional<T: ToString>(doc: &mut toml_edit::Document, key: &str, value: Option<T>) {
+	match value {
+		Some(value) => write_value(&mut doc[key], value.to_string()),
+		None => {
+			doc.as_table_mut().remove(key);
+		}
+	}
+}
+
+/* TOML hex integers can't have a sign, so a negative address stays a decimal one */
+fn hex_address(address: i64) -> toml_edit::Value {
+	if address < 0 {
+		return address.into();
+	}
+	format!("{:#X}", address)
+		.parse()
+		.expect("a hex integer is valid TOML")
+}
+
+fn address_rank(key: &str) -> u8 {
+	match key {
+		"address" | "signature" => 0,
+		"offset" => 1,
+		_ => 2,
+	}
+}
+
+/* Written like a hex editor shows them */
+fn hex_byte(byte: u8) -> toml_edit::Value {
+	format!("0x{:02X}", byte)
+		.parse()
+		.expect("a hex integer is valid TOML")
+}
+
+fn read_translation(doc: &toml_edit::Item, file: &str, reader: &mut ProblemReader) -> Translation {
+	let mut translation_data = Translation {
+		language: reader.str(doc, "language").unwrap_or(file).to_string(),
+		author: reader.str(doc, "author").unwrap_or_default().to_string(),
+		enabled: reader.bool(doc, "enabled").unwrap_or_default(),
+		translations: vec![],
+		file: file.to_string(),
+	};
+
+	let tables = match reader.array_of_tables(doc, "translation") {
+		Some(tables) => tables,
+		None => return translation_data,
+	};
+	for i in 0..tables.as_array_of_tables().unwrap().len() {
+		let table = &tables[i];
+		let mut reader = reader.scoped(&format!("translation[{}]", i));
+		let state = if table.get("state").is_some() {
+			reader
+				.parse::<SubGameStates>(table, "state")
+				.unwrap_or_default()
+		} else {
+			SubGameStates::SUB_MAX
+		};
+		translation_data.translations.push(InternalTranslation {
+			old: reader.str(table, "old").unwrap_or_default().to_string(),
+			new: reader.str(table, "new").unwrap_or_default().to_string(),
+			state,
+		});
+	}
+	translation_data
+}
+
+fn write_translation(translation_data: &Translation, doc: &mut toml_edit::Document) {
+	write_value(&mut doc["language"], translation_data.language.as_str());
+	write_value(&mut doc["author"], translation_data.author.as_str());
+	write_value(&mut doc["enabled"], translation_data.enabled);
+
+	let tables =
+		resize_array_of_tables(&mut doc["translation"], translation_data.translations.len());
+	for (table, translation) in tables.iter_mut().zip(&translation_data.translations) {
+		write_value(&mut table["old"], translation.old.as_str());
+		write_value(&mut table["new"], translation.new.as_str());
+		if table.contains_key("state") {
+			let state_str: &'static str = translation.state.into();
+			write_value(&mut table["state"], state_str);
+		}
+	}
+}
+
+fn read_dir_files(dir: &Path, sub_dir: &str, problems: &mut Vec<Problem>) -> Vec<String> {
+	let entries = match std::fs::read_dir(dir.join(sub_dir)) {
+		Ok(entries) => entries,
+		Err(err) => {
+			problems.push(Problem {
+				file: sub_dir.to_string(),
+				key: String::new(),
+				line_col: None,
+				kind: ProblemKind::Io(err.to_string()),
+			});
+			return vec![];
+		}
+	};
+	let mut files: Vec<String> = entries
+		.filter_map(|entry| entry.ok())
+		.filter(|entry| {
+			let path = entry.path();
+			/* Skips leftovers like .name.toml.tmp from an interrupted save */
+			path.is_file() && path.extension().is_some_and(|ext| ext == "toml")
+		})
+		.map(|entry| format!("{}/{}", sub_dir, entry.file_name().to_string_lossy()))
+		.collect();
+	files.sort();
+	files
+}
+
+fn save_document(
+	dir: &Path,
+	file: &str,
+	problems: &mut Vec<Problem>,
+	check: impl FnOnce(&toml_edit::Item, &mut ProblemReader),
+	apply: impl FnOnce(&mut toml_edit::Document),
+) {
+	let mut doc = match read_document(dir, file, problems) {
+		Some(doc) => doc,
+		None => return,
+	};
+	let mut found = vec![];
+	check(doc.as_item(), &mut ProblemReader::new(file, &mut found));
+	if has_problems(&found, file) {
+		problems.extend(found);
+		return;
+	}
+
+	let original = doc.to_string();
+	apply(&mut doc);
+	let modified = doc.to_string();
+	if modified == original {
+		return;
+	}
+	if let Err(err) = write_atomic(dir, file, &modified) {
+		problems.push(Problem {
+			file: file.to_string(),
+			key: String::new(),
+			line_col: None,
+			kind: ProblemKind::Write(err.to_string()),
+		});
+	}
+}
+
+fn create_document(
+	dir: &Path,
+	file: &str,
+	problems: &mut Vec<Problem>,
+	apply: impl FnOnce(&mut toml_edit::Document),
+) {
+	let mut doc = toml_edit::Document::new();
+	apply(&mut doc);
+	let result = match dir.join(file).parent() {
+		Some(parent) => std::fs::create_dir_all(parent),
+		None => Ok(()),
+	}
+	.and_then(|_| write_atomic(dir, file, &doc.to_string()));
+	if let Err(err) = result {
+		problems.push(Problem {
+			file: file.to_string(),
+			key: String::new(),
+			line_col: None,
+			kind: ProblemKind::Write(err.to_string()),
+		});
+	}
+}
+
+fn same_value(a: &toml_edit::Value, b: &toml_edit::Value) -> bool {
+	match (a, b) {
+		(toml_edit::Value::Integer(a), toml_edit::Value::Integer(b)) => a.value() == b.value(),
+		/* 1.50 and 1.5 are the same number, the file keeps however it was written */
+		(toml_edit::Value::Float(a), toml_edit::Value::Float(b)) => a.value() == b.value(),
+		(toml_edit::Value::Boolean(a), toml_edit::Value::Boolean(b)) => a.value() == b.value(),
+		(toml_edit::Value::String(a), toml_edit::Value::String(b)) => a.value() == b.value(),
+		(toml_edit::Value::Array(a), toml_edit::Value::Array(b)) => {
+			a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b))
+		}
+		_ => false,
+	}
+}
+
+/* Only touches the value if it changed, keeping its comments and whitespace */
+pub(crate) fn write_value<V: Into<toml_edit::Value>>(item: &mut toml_edit::Item, value: V) {
+	let mut value = value.into();
+	if let Some(old) = item.as_value() {
+		if same_value(old, &value) {
+			return;
+		}
+		*value.decor_mut() = old.decor().clone();
+	}
+	*item = toml_edit::Item::Value(value);
+}
+
+pub(crate) fn write_array(item: &mut toml_edit::Item, values: Vec<toml_edit::Value>) {
+	let array = match item.as_array_mut() {
+		Some(array) => array,
+		None => {
+			write_value(item, values.into_iter().collect::<toml_edit::Array>());
+			return;
+		}
+	};
+	/* The space before the closing bracket belongs to the last element */
+	let last_suffix = match array.iter().last() {
+		Some(last) if array.len() > values.len() => last.decor().suffix().map(str::to_string),
+		_ => None,
+	};
+	while array.len() > values.len() {
+		array.remove(array.len() - 1);
+	}
+	for (i, value) in values.into_iter().enumerate() {
+		match array.get(i) {
+			Some(old) if same_value(old, &value) => {}
+			Some(_) => {
+				array.replace(i, value);
+			}
+			None => array.push(value),
+		}
+	}
+	if let (Some(suffix), Some(last)) = (last_suffix, array.iter_mut().last()) {
+		last.decor_mut().set_suffix(suffix);
+	}
+}
+
+/* Each entry gets the table it was read from so its comments move with it, a table is only
+ * reused once so a duplicated entry gets a new one. The reused tables keep their place in
+ * the document between them */
+fn take_tables(
+	item: &mut toml_edit::Item,
+	sources: impl Iterator<Item = Option<usize>>,
+) -> Vec<toml_edit::Table> {
+	let mut old: Vec<Option<toml_edit::Table>> = match item.as_array_of_tables() {
+		Some(tables) => tables.iter().cloned().map(Some).collect(),
+		None => vec![],
+	};
+	let mut tables: Vec<toml_edit::Table> = sources
+		.map(|source| {
+			source
+				.and_then(|i| old.get_mut(i).and_then(Option::take))
+				.unwrap_or_else(toml_edit::Table::new)
+		})
+		.collect();
+	let mut positions: Vec<usize> = tables.iter().filter_map(|table| table.position()).collect();
+	positions.sort_unstable();
+	for (table, position) in tables
+		.iter_mut()
+		.filter(|table| table.position().is_some())
+		.zip(positions)
+	{
+		table.set_position(position);
+	}
+	tables
+}
+
+fn resize_array_of_tables(item: &mut toml_edit::Item, len: usize) -> &mut toml_edit::ArrayOfTables {
+	if !item.is_array_of_tables() {
+		*item = toml_edit::Item::ArrayOfTables(toml_edit::ArrayOfTables::new());
+	}
+	let tables = item.as_array_of_tables_mut().unwrap();
+	while tables.len() > len {
+		tables.remove(tables.len() - 1);
+	}
+	while tables.len() < len {
+		tables.push(toml_edit::Table::new());
+	}
+	tables
+}
+
+pub(crate) fn buttons_to_values(vec: &[Buttons]) -> Vec<toml_edit::Value> {
+	vec.iter()
+		.map(|button| {
+			let button_str: &'static str = button.into();
+			button_str.into()
+		})
+		.collect()
+}
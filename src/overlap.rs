@@ -0,0 +1,88 @@
+use crate::Patch;
+use std::{fmt, ops::Range};
+
+/* Two enabled patch files writing some of the same bytes, whichever the DLL applies last wins */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Overlap {
+	pub first_file: String,
+	pub first_address: i64,
+	pub second_file: String,
+	pub second_address: i64,
+	/* The bytes both write */
+	pub bytes: Range<i64>,
+}
+
+impl Overlap {
+	pub fn involves(&self, file: &str) -> bool {
+		self.first_file == file || self.second_file == file
+	}
+
+	pub fn involves_entry(&self, file: &str, address: i64) -> bool {
+		self.first_file == file && self.first_address == address
+			|| self.second_file == file && self.second_address == address
+	}
+
+	/* Describes the overlap from the point of view of one of its files */
+	pub fn describe(&self, file: &str) -> String {
+		let (address, other_file, other_address) = if self.first_file == file {
+			(self.first_address, &self.second_file, self.second_address)
+		} else {
+			(self.second_address, &self.first_file, self.first_address)
+		};
+		format!(
+			"{:#X} overlaps {} at {:#X}",
+			address, other_file, other_address
+		)
+	}
+}
+
+impl fmt::Display for Overlap {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} at {:#X} and {} at {:#X} both write {:#X}..{:#X}",
+			self.first_file,
+			self.first_address,
+			self.second_file,
+			self.second_address,
+			self.bytes.start,
+			self.bytes.end
+		)
+	}
+}
+
+/* Only entries from different files are compared, overlaps within one file are up to its author */
+pub fn find_overlaps(patches: &[Patch]) -> Vec<Overlap> {
+	let enabled: Vec<&Patch> = patches.iter().filter(|patch| patch.enabled).collect();
+	let mut overlaps = vec![];
+	for (i, patch) in enabled.iter().enumerate() {
+		for other in &enabled[i + 1..] {
+			/* Entries with a signature that wasn't found have nowhere to be */
+			for entry in patch
+				.patches
+				.iter()
+				.filter(|entry| entry.target().is_some())
+			{
+				let span = entry.span();
+				for other_entry in other
+					.patches
+					.iter()
+					.filter(|entry| entry.target().is_some())
+				{
+					let other_span = other_entry.span();
+					let bytes = span.start.max(other_span.start)..span.end.min(other_span.end);
+					if bytes.start < bytes.end {
+						overlaps.push(Overlap {
+							first_file: patch.file.clone(),
+							first_address: entry.address,
+							second_file: other.file.clone(),
+							second_address: other_entry.address,
+							bytes,
+						});
+					}
+				}
+			}
+		}
+	}
+	overlaps
+}
@@ -0,0 +1,309 @@
+use crate::{DataTypes, InternalPatch, Patch};
+use std::{fmt, ops::Range, path::Path};
+
+/* The executable patch addresses refer to, in the game folder */
+pub const GAME_EXE: &str = "DivaMegaMix.exe";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PeError {
+	Io(String),
+	/* Not a PE image, or one cut short */
+	Invalid(&'static str),
+	/* The address is outside every section */
+	Unmapped(i64),
+	/* In a section but past the bytes stored in the file, the game fills those in at runtime */
+	NotInFile(i64),
+	/* The entry's signature was not found so it has no address */
+	Unresolved,
+	/* A data value the entry's data type can't hold */
+	DoesNotFit(i64, DataTypes),
+}
+
+impl fmt::Display for PeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			PeError::Io(err) => write!(f, "could not read {}: {}", GAME_EXE, err),
+			PeError::Invalid(err) => write!(f, "{} is not a valid executable: {}", GAME_EXE, err),
+			PeError::Unmapped(address) => {
+				write!(f, "{:#X} is not inside {}", address, GAME_EXE)
+			}
+			PeError::NotInFile(address) => write!(
+				f,
+				"{:#X} is only filled in once the game runs, {} has no bytes for it",
+				address, GAME_EXE
+			),
+			PeError::Unresolved => write!(f, "the signature was not found, there is no address"),
+			PeError::DoesNotFit(value, data_type) => {
+				write!(
+					f,
+					"{} ({:#X}) does not fit in {:?}",
+					value, value, data_type
+				)
+			}
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Section {
+	pub name: String,
+	/* Relative to the image base */
+	pub virtual_address: u32,
+	pub virtual_size: u32,
+	pub raw_offset: u32,
+	pub raw_size: u32,
+}
+
+/* An executable as it is on disk, with what is needed to find a virtual address in it */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PeImage {
+	pub image_base: u64,
+	pub headers_size: u32,
+	pub sections: Vec<Section>,
+	pub data: Vec<u8>,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, PeError> {
+	data.get(offset..offset + 2)
+		.map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+		.ok_or(PeError::Invalid("the headers are cut short"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, PeError> {
+	data.get(offset..offset + 4)
+		.map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+		.ok_or(PeError::Invalid("the headers are cut short"))
+}
+
+impl PeImage {
+	pub fn load(path: &Path) -> Result<PeImage, PeError> {
+		let data = std::fs::read(path).map_err(|err| PeError::Io(err.to_string()))?;
+		PeImage::parse(data)
+	}
+
+	pub fn parse(data: Vec<u8>) -> Result<PeImage, PeError> {
+		if data.get(..2) != Some(b"MZ") {
+			return Err(PeError::Invalid("no MZ header"));
+		}
+		let pe = read_u32(&data, 0x3C)? as usize;
+		if data.get(pe..pe + 4) != Some(b"PE\0\0") {
+			return Err(PeError::Invalid("no PE header"));
+		}
+		let coff = pe + 4;
+		let section_count = read_u16(&data, coff + 2)? as usize;
+		let optional_size = read_u16(&data, coff + 16)? as usize;
+		let optional = coff + 20;
+		let image_base = match read_u16(&data, optional)? {
+			0x10B => read_u32(&data, optional + 28)? as u64,
+			0x20B => {
+				read_u32(&data, optional + 24)? as u64
+					| (read_u32(&data, optional + 28)? as u64) << 32
+			}
+			_ => return Err(PeError::Invalid("unknown optional header")),
+		};
+		let headers_size = read_u32(&data, optional + 60)?;
+
+		let mut sections = vec![];
+		for i in 0..section_count {
+			let header = optional + optional_size + i * 40;
+			let name = data
+				.get(header..header + 8)
+				.ok_or(PeError::Invalid("the section table is cut short"))?;
+			sections.push(Section {
+				name: String::from_utf8_lossy(name)
+					.trim_end_matches('\0')
+					.to_string(),
+				virtual_size: read_u32(&data, header + 8)?,
+				virtual_address: read_u32(&data, header + 12)?,
+				raw_size: read_u32(&data, header + 16)?,
+				raw_offset: read_u32(&data, header + 20)?,
+			});
+		}
+		Ok(PeImage {
+			image_base,
+			headers_size,
+			sections,
+			data,
+		})
+	}
+
+	/* File offset of the byte the game loads at address, and how many bytes from there
+	 * are stored in the file */
+	fn locate(&self, address: i64) -> Result<(usize, usize), PeError> {
+		let rva = (address as u64)
+			.checked_sub(self.image_base)
+			.filter(|rva| *rva <= u32::MAX as u64)
+			.ok_or(PeError::Unmapped(address))? as u32;
+		if rva < self.headers_size {
+			return Ok((rva as usize, (self.headers_size - rva) as usize));
+		}
+		let section = self
+			.sections
+			.iter()
+			.find(|section| {
+				let size = section.virtual_size.max(section.raw_size);
+				rva >= section.virtual_address && rva - section.virtual_address < size
+			})
+			.ok_or(PeError::Unmapped(address))?;
+		let into = rva - section.virtual_address;
+		if into >= section.raw_size {
+			return Err(PeError::NotInFile(address));
+		}
+		Ok((
+			section.raw_offset as usize + into as usize,
+			(section.raw_size - into) as usize,
+		))
+	}
+
+	pub fn file_offset(&self, address: i64) -> Result<usize, PeError> {
+		self.locate(address).map(|(offset, _)| offset)
+	}
+
+	/* The bytes have to be stored in the file one after another */
+	fn range(&self, address: i64, len: usize) -> Result<Range<usize>, PeError> {
+		let (offset, available) = self.locate(address)?;
+		if len > available {
+			return Err(PeError::NotInFile(address + available as i64));
+		}
+		if offset + len > self.data.len() {
+			return Err(PeError::Invalid("a section is cut short"));
+		}
+		Ok(offset..offset + len)
+	}
+
+	pub fn read(&self, address: i64, len: usize) -> Result<&[u8], PeError> {
+		self.range(address, len).map(|range| &self.data[range])
+	}
+}
+
+/* What a patch entry replaces in the executable */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Comparison {
+	pub original: Vec<u8>,
+	pub patched: Vec<u8>,
+	/* The patch expects other original bytes, it was likely made for another version */
+	pub mismatch: bool,
+}
+
+/* Values are checked instead of cut down to the data type */
+pub fn check_data(patch: &InternalPatch) -> Result<(), PeError> {
+	match patch.out_of_range() {
+		Some(value) => Err(PeError::DoesNotFit(value, patch.data_type)),
+		None => Ok(()),
+	}
+}
+
+pub fn compare(image: &PeImage, patch: &InternalPatch) -> Result<Comparison, PeError> {
+	check_data(patch)?;
+	let patched = patch.bytes();
+	let address = patch.target().ok_or(PeError::Unresolved)?;
+	let original = image.read(address, patched.len())?.to_vec();
+	let mismatch = patch
+		.original
+		.as_ref()
+		.is_some_and(|expected| *expected != original);
+	Ok(Comparison {
+		original,
+		patched,
+		mismatch,
+	})
+}
+
+/* One patch entry written into a copy of the executable */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Change {
+	pub file: String,
+	pub address: i64,
+	pub offset: usize,
+	/* Includes what earlier entries wrote to the same bytes */
+	pub original: Vec<u8>,
+	pub patched: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ApplyError {
+	pub file: String,
+	pub address: i64,
+	pub error: PeError,
+}
+
+impl fmt::Display for ApplyError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} at {:#X}: {}", self.file, self.address, self.error)
+	}
+}
+
+impl PeImage {
+	/* The executable with every enabled patch written in, in order, so later files win where
+	 * they overlap. Nothing is returned if an entry can't be placed in the file */
+	pub fn apply(&self, patches: &[Patch]) -> Result<(Vec<u8>, Vec<Change>), Vec<ApplyError>> {
+		let mut data = self.data.clone();
+		let mut changes = vec![];
+		let mut errors = vec![];
+		for patch in patches.iter().filter(|patch| patch.enabled) {
+			for entry in &patch.patches {
+				let patched = entry.bytes();
+				let range = match check_data(entry)
+					.and_then(|_| entry.target().ok_or(PeError::Unresolved))
+					.and_then(|address| self.range(address, patched.len()))
+				{
+					Ok(range) => range,
+					Err(error) => {
+						errors.push(ApplyError {
+							file: patch.file.clone(),
+							address: entry.address,
+							error,
+						});
+						continue;
+					}
+				};
+				let offset = range.start;
+				let bytes = &mut data[range];
+				changes.push(Change {
+					file: patch.file.clone(),
+					address: entry.address,
+					offset,
+					original: bytes.to_vec(),
+					patched: patched.clone(),
+				});
+				bytes.copy_from_slice(&patched);
+			}
+		}
+		if errors.is_empty() {
+			Ok((data, changes))
+		} else {
+			Err(errors)
+		}
+	}
+}
+
+/* Every byte that changed by file offset, grouped by patch entry */
+pub fn change_report(changes: &[Change]) -> String {
+	let mut report = String::new();
+	for change in changes {
+		report += &format!(
+			"{} at {:#X}, file offset {:#X}\n",
+			change.file, change.address, change.offset
+		);
+		let mut changed = false;
+		for (i, (from, to)) in change.original.iter().zip(&change.patched).enumerate() {
+			if from != to {
+				report += &format!("\t{:#X}: {:02X} -> {:02X}\n", change.offset + i, from, to);
+				changed = true;
+			}
+		}
+		if !changed {
+			report += "\tno bytes changed\n";
+		}
+	}
+	report
+}
+
+/* Space separated like a hex editor, e.g. "74 05" */
+pub fn hex_bytes(bytes: &[u8]) -> String {
+	bytes
+		.iter()
+		.map(|byte| format!("{:02X}", byte))
+		.collect::<Vec<_>>()
+		.join(" ")
+}
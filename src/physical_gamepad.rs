@@ -0,0 +1,104 @@
+use crate::{
+	gamepad::{Gamepad, GamepadEvent},
+	Buttons,
+};
+
+/* Controllers plugged into this machine, with buttons named the way SDL names them */
+pub struct PhysicalGamepad {
+	gilrs: gilrs::Gilrs,
+}
+
+impl PhysicalGamepad {
+	pub fn new() -> Result<PhysicalGamepad, String> {
+		match gilrs::Gilrs::new() {
+			Ok(gilrs) => Ok(PhysicalGamepad { gilrs }),
+			Err(err) => Err(format!("could not read controllers: {}", err)),
+		}
+	}
+}
+
+impl Gamepad for PhysicalGamepad {
+	fn name(&self) -> String {
+		let names: Vec<String> = self
+			.gilrs
+			.gamepads()
+			.map(|(_, gamepad)| gamepad.name().to_string())
+			.collect();
+		if names.is_empty() {
+			"No controller connected".to_string()
+		} else {
+			names.join(", ")
+		}
+	}
+
+	fn poll(&mut self) -> Vec<GamepadEvent> {
+		let mut events = vec![];
+		while let Some(event) = self.gilrs.next_event() {
+			match event.event {
+				gilrs::EventType::ButtonPressed(button, _) => {
+					events.extend(sdl_button(button).map(GamepadEvent::Pressed))
+				}
+				gilrs::EventType::ButtonReleased(button, _) => {
+					events.extend(sdl_button(button).map(GamepadEvent::Released))
+				}
+				gilrs::EventType::AxisChanged(axis, value, _) => {
+					events.extend(stick_events(axis, value))
+				}
+				_ => {}
+			}
+		}
+		events
+	}
+}
+
+/* Buttons without an SDL name, like C and Z on old pads, are left out */
+pub fn sdl_button(button: gilrs::Button) -> Option<Buttons> {
+	use gilrs::Button;
+	Some(match button {
+		Button::South => Buttons::SDL_A,
+		Button::East => Buttons::SDL_B,
+		Button::West => Buttons::SDL_X,
+		Button::North => Buttons::SDL_Y,
+		Button::Select => Buttons::SDL_BACK,
+		Button::Mode => Buttons::SDL_GUIDE,
+		Button::Start => Buttons::SDL_START,
+		Button::LeftTrigger => Buttons::SDL_LSHOULDER,
+		Button::LeftTrigger2 => Buttons::SDL_LTRIGGER,
+		Button::RightTrigger => Buttons::SDL_RSHOULDER,
+		Button::RightTrigger2 => Buttons::SDL_RTRIGGER,
+		Button::DPadUp => Buttons::SDL_DPAD_UP,
+		Button::DPadLeft => Buttons::SDL_DPAD_LEFT,
+		Button::DPadDown => Buttons::SDL_DPAD_DOWN,
+		Button::DPadRight => Buttons::SDL_DPAD_RIGHT,
+		Button::LeftThumb => Buttons::SDL_LSTICK_PRESS,
+		Button::RightThumb => Buttons::SDL_RSTICK_PRESS,
+		_ => return None,
+	})
+}
+
+/* How far a stick has to be pushed to count as a direction */
+pub const STICK_THRESHOLD: f32 = 0.5;
+
+/* A stick axis moving presses the direction it is pushed past the threshold and releases the
+ * others on that axis. Up is positive */
+pub fn stick_events(axis: gilrs::Axis, value: f32) -> Vec<GamepadEvent> {
+	use gilrs::Axis;
+	let (negative, positive) = match axis {
+		Axis::LeftStickX => (Buttons::SDL_LSTICK_LEFT, Buttons::SDL_LSTICK_RIGHT),
+		Axis::LeftStickY => (Buttons::SDL_LSTICK_DOWN, Buttons::SDL_LSTICK_UP),
+		Axis::RightStickX => (Buttons::SDL_RSTICK_LEFT, Buttons::SDL_RSTICK_RIGHT),
+		Axis::RightStickY => (Buttons::SDL_RSTICK_DOWN, Buttons::SDL_RSTICK_UP),
+		_ => return vec![],
+	};
+	let event = |button, pushed| {
+		if pushed {
+			GamepadEvent::Pressed(button)
+		} else {
+			GamepadEvent::Released(button)
+		}
+	};
+	vec![
+		event(negative, value <= -STICK_THRESHOLD),
+		event(positive, value >= STICK_THRESHOLD),
+	]
+}
@@ -0,0 +1,180 @@
+use crate::{
+	actions::find_action,
+	backup::write_atomic,
+	model::{buttons_to_values, write_array},
+	problems::{read_document, Problem, ProblemKind, ProblemReader},
+	toml_stems, valid_file_stem, Buttons, Keyconfig,
+};
+use std::path::Path;
+
+pub const PRESET_DIR: &str = "presets";
+
+const BUNDLED: &[(&str, &str)] = &[
+	(
+		"Default keyboard",
+		include_str!("../presets/default_keyboard.toml"),
+	),
+	(
+		"SDL controller",
+		include_str!("../presets/sdl_controller.toml"),
+	),
+];
+
+/* Bindings to share between players, written like keyconfig.toml.
+ * Actions a preset leaves out keep their current bindings */
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Preset {
+	pub name: String,
+	pub bindings: Vec<(String, Vec<Buttons>)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PresetChange {
+	pub action: String,
+	pub from: Vec<Buttons>,
+	pub to: Vec<Buttons>,
+}
+
+impl Preset {
+	pub fn export(name: &str, keyconfig: &Keyconfig) -> Preset {
+		Preset {
+			name: name.to_string(),
+			bindings: keyconfig
+				.actions()
+				.filter(|(action, _)| find_action(action).is_some())
+				.map(|(action, vec)| (action.to_string(), vec.clone()))
+				.collect(),
+		}
+	}
+
+	/* Every action and button name is checked, nothing is returned if one is unknown */
+	pub fn parse(name: &str, file: &str, text: &str) -> Result<Preset, Vec<Problem>> {
+		let doc = text.parse::<toml_edit::Document>().map_err(|err| {
+			vec![Problem {
+				file: file.to_string(),
+				key: String::new(),
+				line_col: err.line_col().map(|(line, col)| (line + 1, col + 1)),
+				kind: ProblemKind::Parse(err.to_string().trim().to_string()),
+			}]
+		})?;
+		Preset::from_document(name, file, &doc)
+	}
+
+	pub fn load(dir: &Path, name: &str) -> Result<Preset, Vec<Problem>> {
+		let file = preset_file(name);
+		let mut problems = vec![];
+		match read_document(dir, &file, &mut problems) {
+			Some(doc) => Preset::from_document(name, &file, &doc),
+			None => Err(problems),
+		}
+	}
+
+	fn from_document(
+		name: &str,
+		file: &str,
+		doc: &toml_edit::Document,
+	) -> Result<Preset, Vec<Problem>> {
+		let mut problems = vec![];
+		let mut reader = ProblemReader::new(file, &mut problems);
+		let mut bindings = vec![];
+		for (action, item) in doc.iter() {
+			if find_action(action).is_none() {
+				reader.invalid_value(action, action);
+			} else if !item.is_array() {
+				reader.wrong_type(action, "array", item);
+			} else if let Some(vec) = reader.parsed_array(doc.as_item(), action) {
+				bindings.push((action.to_string(), vec));
+			}
+		}
+		if problems.is_empty() {
+			Ok(Preset {
+				name: name.to_string(),
+				bindings,
+			})
+		} else {
+			Err(problems)
+		}
+	}
+
+	/* Like profiles, an existing file is updated so its comments survive */
+	pub fn save(&self, dir: &Path) -> Result<(), Problem> {
+		let file = preset_file(&self.name);
+		let problem = |kind| Problem {
+			file: file.clone(),
+			key: String::new(),
+			line_col: None,
+			kind,
+		};
+		if !valid_file_stem(&self.name) {
+			return Err(problem(ProblemKind::InvalidValue(format!(
+				"{:?}",
+				self.name
+			))));
+		}
+
+		let mut doc = if dir.join(&file).exists() {
+			let mut problems = vec![];
+			match read_document(dir, &file, &mut problems) {
+				Some(doc) => doc,
+				None => return Err(problems.remove(0)),
+			}
+		} else {
+			toml_edit::Document::new()
+		};
+		let stale: Vec<String> = doc
+			.iter()
+			.map(|(key, _)| key.to_string())
+			.filter(|key| !self.bindings.iter().any(|(action, _)| action == key))
+			.collect();
+		for key in stale {
+			doc.remove(&key);
+		}
+		for (action, vec) in &self.bindings {
+			write_array(&mut doc[action], buttons_to_values(vec));
+		}
+
+		std::fs::create_dir_all(dir.join(PRESET_DIR))
+			.and_then(|_| write_atomic(dir, &file, &doc.to_string()))
+			.map_err(|err| problem(ProblemKind::Write(err.to_string())))
+	}
+
+	/* What applying would change, in preset order */
+	pub fn changes(&self, keyconfig: &Keyconfig) -> Vec<PresetChange> {
+		self.bindings
+			.iter()
+			.filter_map(|(action, to)| {
+				let from = keyconfig.get(action).cloned().unwrap_or_default();
+				(from != *to).then(|| PresetChange {
+					action: action.clone(),
+					from,
+					to: to.clone(),
+				})
+			})
+			.collect()
+	}
+
+	pub fn apply(&self, keyconfig: &mut Keyconfig) {
+		for (action, vec) in &self.bindings {
+			keyconfig.set(action, vec.clone());
+		}
+	}
+}
+
+pub fn preset_file(name: &str) -> String {
+	format!("{}/{}.toml", PRESET_DIR, name)
+}
+
+/* Presets in the game folder, bundled ones are separate */
+pub fn presets(dir: &Path) -> Vec<String> {
+	toml_stems(&dir.join(PRESET_DIR))
+}
+
+pub fn bundled() -> Vec<Preset> {
+	BUNDLED
+		.iter()
+		.map(|(name, text)| {
+			Preset::parse(name, &format!("bundled/{}", name), text)
+				.expect("bundled presets are valid")
+		})
+		.collect()
+}
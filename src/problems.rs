@@ -0,0 +1,362 @@
+use std::{fmt, path::Path, str::FromStr};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ProblemKind {
+	Io(String),
+	Write(String),
+	Parse(String),
+	MissingKey,
+	WrongType {
+		expected: &'static str,
+		found: &'static str,
+	},
+	InvalidValue(String),
+	/* Read fine but outside what the game accepts */
+	OutOfRange(String),
+	/* A patch signature that could not be found in the executable */
+	Signature(String),
+	/* Not in the file, the default shown here is used and written on the next save */
+	FilledIn(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Problem {
+	pub file: String,
+	pub key: String,
+	/* 1-based, only known for syntax errors */
+	pub line_col: Option<(usize, usize)>,
+	pub kind: ProblemKind,
+}
+
+impl fmt::Display for ProblemKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ProblemKind::Io(err) => write!(f, "could not read file: {}", err),
+			ProblemKind::Write(err) => write!(f, "could not write file: {}", err),
+			ProblemKind::Parse(err) => write!(f, "invalid TOML: {}", err),
+			ProblemKind::MissingKey => write!(f, "missing key"),
+			ProblemKind::WrongType { expected, found } => {
+				write!(f, "expected {}, found {}", expected, found)
+			}
+			ProblemKind::InvalidValue(value) => write!(f, "invalid value {}", value),
+			ProblemKind::OutOfRange(message) | ProblemKind::Signature(message) => {
+				write!(f, "{}", message)
+			}
+			ProblemKind::FilledIn(default) => {
+				write!(f, "missing, filled in with the default {}", default)
+			}
+		}
+	}
+}
+
+impl fmt::Display for Problem {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.file)?;
+		if let Some((line, col)) = self.line_col {
+			write!(f, ":{}:{}", line, col)?;
+		}
+		if !self.key.is_empty() {
+			write!(f, ": {}", self.key)?;
+		}
+		write!(f, ": {}", self.kind)
+	}
+}
+
+/* Out of range values, missing signatures and keys filled in from the defaults don't stop
+ * a file from being saved, saving is how they get fixed */
+pub fn has_problems(problems: &[Problem], file: &str) -> bool {
+	problems.iter().any(|problem| {
+		problem.file == file
+			&& !matches!(
+				problem.kind,
+				ProblemKind::OutOfRange(_) | ProblemKind::Signature(_) | ProblemKind::FilledIn(_)
+			)
+	})
+}
+
+pub fn read_document(
+	dir: &Path,
+	file: &str,
+	problems: &mut Vec<Problem>,
+) -> Option<toml_edit::Document> {
+	let file = file.to_string();
+	let text = match std::fs::read_to_string(dir.join(&file)) {
+		Ok(text) => text,
+		Err(err) => {
+			problems.push(Problem {
+				file,
+				key: String::new(),
+				line_col: None,
+				kind: ProblemKind::Io(err.to_string()),
+			});
+			return None;
+		}
+	};
+	match text.parse::<toml_edit::Document>() {
+		Ok(doc) => Some(doc),
+		Err(err) => {
+			problems.push(Problem {
+				file,
+				key: String::new(),
+				line_col: err.line_col().map(|(line, col)| (line + 1, col + 1)),
+				kind: ProblemKind::Parse(err.to_string().trim().to_string()),
+			});
+			None
+		}
+	}
+}
+
+/* Typed lookups that record a Problem instead of panicking */
+pub struct ProblemReader<'a> {
+	file: String,
+	prefix: String,
+	problems: &'a mut Vec<Problem>,
+}
+
+impl<'a> ProblemReader<'a> {
+	pub fn new(file: &str, problems: &'a mut Vec<Problem>) -> Self {
+		ProblemReader {
+			file: file.to_string(),
+			prefix: String::new(),
+			problems,
+		}
+	}
+
+	pub fn scoped(&mut self, prefix: &str) -> ProblemReader<'_> {
+		ProblemReader {
+			file: self.file.clone(),
+			prefix: self.key_path(prefix),
+			problems: self.problems,
+		}
+	}
+
+	fn key_path(&self, key: &str) -> String {
+		if self.prefix.is_empty() {
+			key.to_string()
+		} else {
+			format!("{}.{}", self.prefix, key)
+		}
+	}
+
+	fn push(&mut self, key: &str, kind: ProblemKind) {
+		let key = self.key_path(key);
+		self.problems.push(Problem {
+			file: self.file.clone(),
+			key,
+			line_col: None,
+			kind,
+		});
+	}
+
+	pub(crate) fn wrong_type(
+		&mut self,
+		key: &str,
+		expected: &'static str,
+		found: &toml_edit::Item,
+	) {
+		self.push(
+			key,
+			ProblemKind::WrongType {
+				expected,
+				found: found.type_name(),
+			},
+		);
+	}
+
+	pub(crate) fn invalid_value(&mut self, key: &str, value: &str) {
+		self.push(key, ProblemKind::InvalidValue(format!("{:?}", value)));
+	}
+
+	/* Dotted keys walk nested tables, e.g. "internalRes.x" */
+	fn lookup<'b>(parent: &'b toml_edit::Item, key: &str) -> Option<&'b toml_edit::Item> {
+		let mut item = parent;
+		for part in key.split('.') {
+			match item.get(part) {
+				Some(child) if !child.is_none() => item = child,
+				_ => return None,
+			}
+		}
+		Some(item)
+	}
+
+	pub fn item<'b>(
+		&mut self,
+		parent: &'b toml_edit::Item,
+		key: &str,
+	) -> Option<&'b toml_edit::Item> {
+		let item = ProblemReader::lookup(parent, key);
+		if item.is_none() {
+			self.push(key, ProblemKind::MissingKey);
+		}
+		item
+	}
+
+	/* For keys with a known default, true when the key is missing and the default is used */
+	pub fn filled_in(
+		&mut self,
+		parent: &toml_edit::Item,
+		key: &str,
+		default: impl fmt::Display,
+	) -> bool {
+		let missing = ProblemReader::lookup(parent, key).is_none();
+		if missing {
+			self.push(key, ProblemKind::FilledIn(default.to_string()));
+		}
+		missing
+	}
+
+	pub fn integer(&mut self, parent: &toml_edit::Item, key: &str) -> Option<i64> {
+		let item = self.item(parent, key)?;
+		let value = item.as_integer();
+		if value.is_none() {
+			self.wrong_type(key, "integer", item);
+		}
+		value
+	}
+
+	pub fn i32(&mut self, parent: &toml_edit::Item, key: &str) -> Option<i32> {
+		let value = self.integer(parent, key)?;
+		let parsed = i32::try_from(value).ok();
+		if parsed.is_none() {
+			self.push(
+				key,
+				ProblemKind::InvalidValue(format!("{}, expected a 32-bit integer", value)),
+			);
+		}
+		parsed
+	}
+
+	pub fn bool(&mut self, parent: &toml_edit::Item, key: &str) -> Option<bool> {
+		let item = self.item(parent, key)?;
+		let value = item.as_bool();
+		if value.is_none() {
+			self.wrong_type(key, "boolean", item);
+		}
+		value
+	}
+
+	pub fn str<'b>(&mut self, parent: &'b toml_edit::Item, key: &str) -> Option<&'b str> {
+		let item = self.item(parent, key)?;
+		let value = item.as_str();
+		if value.is_none() {
+			self.wrong_type(key, "string", item);
+		}
+		value
+	}
+
+	pub fn parse<T: FromStr>(&mut self, parent: &toml_edit::Item, key: &str) -> Option<T> {
+		let value = self.str(parent, key)?;
+		let parsed = T::from_str(value).ok();
+		if parsed.is_none() {
+			self.invalid_value(key, value);
+		}
+		parsed
+	}
+
+	pub fn array_of_tables<'b>(
+		&mut self,
+		parent: &'b toml_edit::Item,
+		key: &str,
+	) -> Option<&'b toml_edit::Item> {
+		let item = self.item(parent, key)?;
+		if !item.is_array_of_tables() {
+			self.wrong_type(key, "array of tables", item);
+			return None;
+		}
+		Some(item)
+	}
+
+	pub fn integer_array(&mut self, parent: &toml_edit::Item, key: &str) -> Option<Vec<i64>> {
+		let item = self.item(parent, key)?;
+		let array = match item.as_array() {
+			Some(array) => array,
+			None => {
+				self.wrong_type(key, "array", item);
+				return None;
+			}
+		};
+		let mut vec = vec![];
+		for (i, value) in array.iter().enumerate() {
+			match value.as_integer() {
+				Some(value) => vec.push(value),
+				None => self.push(
+					&format!("{}[{}]", key, i),
+					ProblemKind::WrongType {
+						expected: "integer",
+						found: value.type_name(),
+					},
+				),
+			}
+		}
+		Some(vec)
+	}
+
+	/* Walks the array itself so a skipped element doesn't shift the index of later ones */
+	pub fn byte_array(&mut self, parent: &toml_edit::Item, key: &str) -> Option<Vec<u8>> {
+		let item = self.item(parent, key)?;
+		let array = match item.as_array() {
+			Some(array) => array,
+			None => {
+				self.wrong_type(key, "array", item);
+				return None;
+			}
+		};
+		let mut bytes = vec![];
+		for (i, value) in array.iter().enumerate() {
+			let element_key = format!("{}[{}]", key, i);
+			match value.as_integer() {
+				Some(value) => match u8::try_from(value) {
+					Ok(byte) => bytes.push(byte),
+					Err(_) => self.push(
+						&element_key,
+						ProblemKind::InvalidValue(format!("{}, expected a byte", value)),
+					),
+				},
+				None => self.push(
+					&element_key,
+					ProblemKind::WrongType {
+						expected: "integer",
+						found: value.type_name(),
+					},
+				),
+			}
+		}
+		Some(bytes)
+	}
+
+	pub fn parsed_array<T: FromStr>(
+		&mut self,
+		parent: &toml_edit::Item,
+		key: &str,
+	) -> Option<Vec<T>> {
+		let item = self.item(parent, key)?;
+		let array = match item.as_array() {
+			Some(array) => array,
+			None => {
+				self.wrong_type(key, "array", item);
+				return None;
+			}
+		};
+		let mut vec = vec![];
+		for (i, value) in array.iter().enumerate() {
+			let element_key = format!("{}[{}]", key, i);
+			match value.as_str() {
+				Some(str) => match T::from_str(str) {
+					Ok(parsed) => vec.push(parsed),
+					Err(_) => self.push(
+						&element_key,
+						ProblemKind::InvalidValue(format!("{:?}", str)),
+					),
+				},
+				None => self.push(
+					&element_key,
+					ProblemKind::WrongType {
+						expected: "string",
+						found: value.type_name(),
+					},
+				),
+			}
+		}
+		Some(vec)
+	}
+}
@@ -0,0 +1,193 @@
+use crate::{
+	backup::write_atomic,
+	model::{read_config, read_keyconfig, write_config, write_keyconfig, write_value},
+	problems::{has_problems, read_document, Problem, ProblemKind, ProblemReader},
+	toml_stems, valid_file_stem, AllConfig, Config, Keyconfig,
+};
+use std::path::Path;
+
+pub const PROFILE_DIR: &str = "profiles";
+
+/* A named snapshot of the settings a player switches between,
+ * stored as profiles/<name>.toml in the game folder */
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Profile {
+	pub name: String,
+	pub config: Config,
+	pub keyconfig: Keyconfig,
+	/* Enabled state by file, e.g. ("patches/nocredits.toml", true) */
+	pub patches: Vec<(String, bool)>,
+	pub translations: Vec<(String, bool)>,
+}
+
+impl Profile {
+	pub fn snapshot(name: &str, all: &AllConfig) -> Profile {
+		Profile {
+			name: name.to_string(),
+			config: all.config.clone(),
+			keyconfig: all.keyconfig.clone(),
+			patches: all
+				.patches
+				.iter()
+				.map(|patch| (patch.file.clone(), patch.enabled))
+				.collect(),
+			translations: all
+				.translations
+				.iter()
+				.map(|translation| (translation.file.clone(), translation.enabled))
+				.collect(),
+		}
+	}
+
+	/* Files the profile does not know about keep their current state */
+	pub fn apply(&self, all: &mut AllConfig) {
+		all.config = self.config.clone();
+		all.keyconfig = self.keyconfig.clone();
+		for patch in &mut all.patches {
+			if let Some((_, enabled)) = self.patches.iter().find(|(file, _)| *file == patch.file) {
+				patch.enabled = *enabled;
+			}
+		}
+		for translation in &mut all.translations {
+			if let Some((_, enabled)) = self
+				.translations
+				.iter()
+				.find(|(file, _)| *file == translation.file)
+			{
+				translation.enabled = *enabled;
+			}
+		}
+	}
+
+	pub fn load(dir: &Path, name: &str) -> Result<Profile, Vec<Problem>> {
+		let file = profile_file(name);
+		let mut problems = vec![];
+		let doc = match read_document(dir, &file, &mut problems) {
+			Some(doc) => doc,
+			None => return Err(problems),
+		};
+		let mut reader = ProblemReader::new(&file, &mut problems);
+		let mut profile = Profile {
+			name: name.to_string(),
+			..Default::default()
+		};
+		if let Some(config) = reader.item(doc.as_item(), "config") {
+			profile.config = read_config(config, &mut reader.scoped("config"));
+		}
+		if let Some(keyconfig) = reader.item(doc.as_item(), "keyconfig") {
+			profile.keyconfig = read_keyconfig(keyconfig, &mut reader.scoped("keyconfig"));
+		}
+		profile.patches = read_enabled(doc.as_item(), "patches", &mut reader);
+		profile.translations = read_enabled(doc.as_item(), "translations", &mut reader);
+
+		/* Keys added to the game after the profile was saved get their default */
+		if !has_problems(&problems, &file) {
+			Ok(profile)
+		} else {
+			Err(problems)
+		}
+	}
+
+	/* Updates an existing profile in place so hand written comments survive */
+	pub fn save(&self, dir: &Path) -> Result<(), Problem> {
+		let file = profile_file(&self.name);
+		let problem = |kind| Problem {
+			file: file.clone(),
+			key: String::new(),
+			line_col: None,
+			kind,
+		};
+		if !valid_file_stem(&self.name) {
+			return Err(problem(ProblemKind::InvalidValue(format!(
+				"{:?}",
+				self.name
+			))));
+		}
+
+		let mut doc = if dir.join(&file).exists() {
+			let mut problems = vec![];
+			match read_document(dir, &file, &mut problems) {
+				Some(doc) => doc,
+				None => return Err(problems.remove(0)),
+			}
+		} else {
+			toml_edit::Document::new()
+		};
+		for key in ["config", "keyconfig"] {
+			if doc.get(key).is_none() {
+				doc[key] = toml_edit::table();
+			}
+		}
+		write_config(&self.config, &mut doc["config"]);
+		write_keyconfig(&self.keyconfig, &mut doc["keyconfig"]);
+		write_enabled(&mut doc["patches"], &self.patches);
+		write_enabled(&mut doc["translations"], &self.translations);
+
+		std::fs::create_dir_all(dir.join(PROFILE_DIR))
+			.and_then(|_| write_atomic(dir, &file, &doc.to_string()))
+			.map_err(|err| problem(ProblemKind::Write(err.to_string())))
+	}
+}
+
+pub fn profile_file(name: &str) -> String {
+	format!("{}/{}.toml", PROFILE_DIR, name)
+}
+
+pub fn profiles(dir: &Path) -> Vec<String> {
+	toml_stems(&dir.join(PROFILE_DIR))
+}
+
+pub fn delete_profile(dir: &Path, name: &str) -> std::io::Result<()> {
+	std::fs::remove_file(dir.join(profile_file(name)))
+}
+
+/* Keys are file paths, which contain dots, so they are looked up directly */
+fn read_enabled(
+	doc: &toml_edit::Item,
+	key: &str,
+	reader: &mut ProblemReader,
+) -> Vec<(String, bool)> {
+	let item = match doc.get(key) {
+		Some(item) if !item.is_none() => item,
+		_ => return vec![],
+	};
+	let table = match item.as_table_like() {
+		Some(table) => table,
+		None => {
+			reader.wrong_type(key, "table", item);
+			return vec![];
+		}
+	};
+	let mut reader = reader.scoped(key);
+	let mut files = vec![];
+	for (file, item) in table.iter() {
+		match item.as_bool() {
+			Some(enabled) => files.push((file.to_string(), enabled)),
+			None => reader.wrong_type(file, "boolean", item),
+		}
+	}
+	files
+}
+
+fn write_enabled(item: &mut toml_edit::Item, files: &[(String, bool)]) {
+	if !item.is_table_like() {
+		*item = toml_edit::table();
+	}
+	let table = item.as_table_like_mut().unwrap();
+	let stale: Vec<String> = table
+		.iter()
+		.map(|(file, _)| file.to_string())
+		.filter(|file| !files.iter().any(|(other, _)| other == file))
+		.collect();
+	for file in stale {
+		table.remove(&file);
+	}
+	for (file, enabled) in files {
+		match table.get_mut(file) {
+			Some(item) => write_value(item, *enabled),
+			None => {
+				table.insert(file, toml_edit::value(*enabled));
+			}
+		}
+	}
+}
@@ -0,0 +1,75 @@
+use std::ops::RangeInclusive;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ResolutionPreset {
+	pub name: &'static str,
+	pub x: i32,
+	pub y: i32,
+}
+
+const fn preset(name: &'static str, x: i32, y: i32) -> ResolutionPreset {
+	ResolutionPreset { name, x, y }
+}
+
+pub const RESOLUTION_PRESETS: &[ResolutionPreset] = &[
+	preset("720p", 1280, 720),
+	preset("1080p", 1920, 1080),
+	preset("1440p", 2560, 1440),
+	preset("4K", 3840, 2160),
+];
+
+pub fn find_preset(x: i32, y: i32) -> Option<&'static ResolutionPreset> {
+	RESOLUTION_PRESETS
+		.iter()
+		.find(|preset| preset.x == x && preset.y == y)
+}
+
+/* Size of the main display in pixels, only known on Windows where the game runs */
+#[cfg(windows)]
+pub fn display_size() -> Option<(i32, i32)> {
+	#[link(name = "user32")]
+	extern "system" {
+		fn GetSystemMetrics(index: i32) -> i32;
+	}
+	const SM_CXSCREEN: i32 = 0;
+	const SM_CYSCREEN: i32 = 1;
+	let (x, y) = unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) };
+	(x > 0 && y > 0).then(|| (x, y))
+}
+
+#[cfg(not(windows))]
+pub fn display_size() -> Option<(i32, i32)> {
+	None
+}
+
+/* Internal resolution as a percentage of a base size, usually the display */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RenderScale {
+	pub base_x: i32,
+	pub base_y: i32,
+	pub percent: i32,
+}
+
+impl RenderScale {
+	pub const PERCENT: RangeInclusive<i32> = 25..=200;
+
+	/* The closest percentage to an existing width */
+	pub fn from_resolution(base_x: i32, base_y: i32, x: i32) -> RenderScale {
+		let percent = if base_x > 0 {
+			((x as i64 * 100 + base_x as i64 / 2) / base_x as i64) as i32
+		} else {
+			100
+		};
+		RenderScale {
+			base_x,
+			base_y,
+			percent: percent.clamp(*Self::PERCENT.start(), *Self::PERCENT.end()),
+		}
+	}
+
+	/* Rounded to the nearest pixel */
+	pub fn size(self) -> (i32, i32) {
+		let scale = |base: i32| ((base as i64 * self.percent as i64 + 50) / 100) as i32;
+		(scale(self.base_x), scale(self.base_y))
+	}
+}
@@ -0,0 +1,204 @@
+use crate::{
+	validation::{self, IntRule},
+	Config,
+};
+use std::fmt;
+
+/* A value from config.toml the launcher has no field for */
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConfigValue {
+	Bool(bool),
+	Integer(i64),
+	Float(f64),
+	String(String),
+	Array(Vec<ConfigValue>),
+}
+
+impl ConfigValue {
+	/* None for dates and other values there is no editor for */
+	pub fn from_toml(value: &toml_edit::Value) -> Option<ConfigValue> {
+		Some(match value {
+			toml_edit::Value::Boolean(value) => ConfigValue::Bool(*value.value()),
+			toml_edit::Value::Integer(value) => ConfigValue::Integer(*value.value()),
+			toml_edit::Value::Float(value) => ConfigValue::Float(*value.value()),
+			toml_edit::Value::String(value) => ConfigValue::String(value.value().clone()),
+			toml_edit::Value::Array(array) => ConfigValue::Array(
+				array
+					.iter()
+					.map(ConfigValue::from_toml)
+					.collect::<Option<_>>()?,
+			),
+			_ => return None,
+		})
+	}
+
+	pub fn to_toml(&self) -> toml_edit::Value {
+		match self {
+			ConfigValue::Bool(value) => (*value).into(),
+			ConfigValue::Integer(value) => (*value).into(),
+			ConfigValue::Float(value) => (*value).into(),
+			ConfigValue::String(value) => value.as_str().into(),
+			ConfigValue::Array(values) => values
+				.iter()
+				.map(ConfigValue::to_toml)
+				.collect::<toml_edit::Array>()
+				.into(),
+		}
+	}
+
+	/* Zero, false or empty of the same type, what a new array element starts as */
+	pub fn empty_like(&self) -> ConfigValue {
+		match self {
+			ConfigValue::Bool(_) => ConfigValue::Bool(false),
+			ConfigValue::Integer(_) => ConfigValue::Integer(0),
+			ConfigValue::Float(_) => ConfigValue::Float(0.0),
+			ConfigValue::String(_) => ConfigValue::String(String::new()),
+			ConfigValue::Array(_) => ConfigValue::Array(vec![]),
+		}
+	}
+
+	pub fn type_name(&self) -> &'static str {
+		match self {
+			ConfigValue::Bool(_) => "boolean",
+			ConfigValue::Integer(_) => "integer",
+			ConfigValue::Float(_) => "float",
+			ConfigValue::String(_) => "string",
+			ConfigValue::Array(_) => "array",
+		}
+	}
+}
+
+/* An empty array has no element to copy the type from, so the first one is picked from these */
+pub static EMPTY_VALUES: [ConfigValue; 5] = [
+	ConfigValue::Bool(false),
+	ConfigValue::Integer(0),
+	ConfigValue::Float(0.0),
+	ConfigValue::String(String::new()),
+	ConfigValue::Array(vec![]),
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Editor {
+	Checkbox,
+	Number,
+	Slider,
+	/* internalRes.x and internalRes.y share one row */
+	Resolution,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ConfigKey {
+	pub key: &'static str,
+	pub name: &'static str,
+	pub group: &'static str,
+	pub description: &'static str,
+	pub editor: Editor,
+	/* Integers only */
+	pub rule: Option<IntRule>,
+	pub default: SchemaDefault,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SchemaDefault {
+	Bool(bool),
+	Integer(i32),
+}
+
+impl fmt::Display for SchemaDefault {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SchemaDefault::Bool(value) => write!(f, "{}", if *value { "on" } else { "off" }),
+			SchemaDefault::Integer(value) => write!(f, "{}", value),
+		}
+	}
+}
+
+pub const CONFIG_GROUPS: &[&str] = &["Display", "Controller"];
+
+/* Every key config.toml is known to have, in the order the config tab shows them */
+pub const CONFIG_SCHEMA: &[ConfigKey] = &[
+	ConfigKey {
+		key: "fps",
+		name: "FPS limit",
+		group: "Display",
+		description: "Frame rate the game is capped at",
+		editor: Editor::Number,
+		rule: Some(validation::FPS),
+		default: SchemaDefault::Integer(60),
+	},
+	ConfigKey {
+		key: "internalRes.x",
+		name: "Internal resolution",
+		group: "Display",
+		description: "Width the game renders at before scaling to the window",
+		editor: Editor::Resolution,
+		rule: Some(validation::INTERNAL_RES_X),
+		default: SchemaDefault::Integer(1920),
+	},
+	ConfigKey {
+		key: "internalRes.y",
+		name: "Internal resolution",
+		group: "Display",
+		description: "Height the game renders at before scaling to the window",
+		editor: Editor::Resolution,
+		rule: Some(validation::INTERNAL_RES_Y),
+		default: SchemaDefault::Integer(1080),
+	},
+	ConfigKey {
+		key: "fullscreen",
+		name: "Fullscreen",
+		group: "Display",
+		description: "Start in exclusive fullscreen instead of a window",
+		editor: Editor::Checkbox,
+		rule: None,
+		default: SchemaDefault::Bool(false),
+	},
+	ConfigKey {
+		key: "rumbleIntensity",
+		name: "Rumble Intensity",
+		group: "Controller",
+		description: "Controller vibration strength in percent, 0 turns it off",
+		editor: Editor::Slider,
+		rule: Some(validation::RUMBLE_INTENSITY),
+		default: SchemaDefault::Integer(100),
+	},
+];
+
+/* Written the way TOML would, "internalRes.x" or "\"a.b\"" for a key with a dot in it */
+pub fn key_path(parts: &[String]) -> String {
+	parts
+		.iter()
+		.map(|part| toml_edit::Key::new(part.as_str()).to_string())
+		.collect::<Vec<_>>()
+		.join(".")
+}
+
+pub fn find_key(key: &str) -> Option<&'static ConfigKey> {
+	CONFIG_SCHEMA.iter().find(|schema| schema.key == key)
+}
+
+impl Config {
+	pub fn int_mut(&mut self, key: &str) -> Option<&mut i32> {
+		match key {
+			"fps" => Some(&mut self.fps),
+			"internalRes.x" => Some(&mut self.internal_res_x),
+			"internalRes.y" => Some(&mut self.internal_res_y),
+			"rumbleIntensity" => Some(&mut self.rumble_intensity),
+			_ => None,
+		}
+	}
+
+	pub fn bool_mut(&mut self, key: &str) -> Option<&mut bool> {
+		match key {
+			"fullscreen" => Some(&mut self.fullscreen),
+			_ => None,
+		}
+	}
+
+	pub fn other_mut(&mut self, key: &str) -> Option<&mut ConfigValue> {
+		self.other
+			.iter_mut()
+			.find(|(path, _)| key_path(path) == key)
+			.map(|(_, value)| value)
+	}
+}
@@ -0,0 +1,209 @@
+use crate::{
+	pe::{PeError, PeImage},
+	problems::{Problem, ProblemKind},
+	InternalPatch, Patch,
+};
+use std::{fmt, str::FromStr};
+
+/* Bytes to look for in the executable, None matches any byte */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Signature(pub Vec<Option<u8>>);
+
+impl FromStr for Signature {
+	type Err = String;
+
+	/* Hex bytes separated by spaces, ? or ?? for any byte, e.g. "48 8B ?? 24 08" */
+	fn from_str(text: &str) -> Result<Signature, String> {
+		let bytes = text
+			.split_whitespace()
+			.map(|token| match token {
+				"?" | "??" => Ok(None),
+				_ if token.len() == 2 => u8::from_str_radix(token, 16)
+					.map(Some)
+					.map_err(|_| format!("{:?} is not a hex byte", token)),
+				_ => Err(format!("{:?} is not a hex byte", token)),
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		if bytes.iter().all(Option::is_none) {
+			return Err("needs at least one byte that isn't a wildcard".to_string());
+		}
+		Ok(Signature(bytes))
+	}
+}
+
+impl fmt::Display for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let tokens: Vec<String> = self
+			.0
+			.iter()
+			.map(|byte| match byte {
+				Some(byte) => format!("{:02X}", byte),
+				None => "??".to_string(),
+			})
+			.collect();
+		write!(f, "{}", tokens.join(" "))
+	}
+}
+
+impl Signature {
+	/* File offsets where the signature starts */
+	pub fn find<'a>(&'a self, data: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+		let len = self.0.len();
+		let (first, first_byte) = self
+			.0
+			.iter()
+			.enumerate()
+			.find_map(|(i, byte)| byte.map(|byte| (i, byte)))
+			.expect("signatures have at least one byte");
+		(0..(data.len() + 1).saturating_sub(len)).filter(move |&start| {
+			data[start + first] == first_byte
+				&& self
+					.0
+					.iter()
+					.zip(&data[start..start + len])
+					.all(|(byte, found)| byte.is_none() || *byte == Some(*found))
+		})
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SignatureError {
+	Invalid(String),
+	Executable(PeError),
+	NotFound,
+	/* Addresses of the first two matches */
+	Ambiguous(Vec<i64>),
+}
+
+impl fmt::Display for SignatureError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SignatureError::Invalid(err) => write!(f, "invalid signature: {}", err),
+			SignatureError::Executable(err) => write!(f, "{}", err),
+			SignatureError::NotFound => write!(f, "signature not found in the executable"),
+			SignatureError::Ambiguous(addresses) => {
+				let addresses: Vec<String> = addresses
+					.iter()
+					.map(|address| format!("{:#X}", address))
+					.collect();
+				write!(
+					f,
+					"signature matches more than once, at {}",
+					addresses.join(" and ")
+				)
+			}
+		}
+	}
+}
+
+/* Used instead of an address so a patch keeps working when the game is updated */
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+pub struct AddressSignature {
+	pub pattern: String,
+	/* Added to where the signature starts */
+	pub offset: i64,
+	/* Found in the executable when patches are loaded, not saved */
+	pub resolved: Option<Result<i64, SignatureError>>,
+}
+
+impl AddressSignature {
+	pub fn new(pattern: &str, offset: i64) -> AddressSignature {
+		AddressSignature {
+			pattern: pattern.to_string(),
+			offset,
+			resolved: None,
+		}
+	}
+
+	pub fn resolve(&mut self, image: Result<&PeImage, &PeError>) {
+		self.resolved = Some(self.find(image));
+	}
+
+	fn find(&self, image: Result<&PeImage, &PeError>) -> Result<i64, SignatureError> {
+		let signature = self
+			.pattern
+			.parse::<Signature>()
+			.map_err(SignatureError::Invalid)?;
+		let image = image.map_err(|err| SignatureError::Executable(err.clone()))?;
+		/* Two are enough to know it is ambiguous */
+		let found: Vec<i64> = signature
+			.find(&image.data)
+			.filter_map(|offset| image.address_of(offset))
+			.take(2)
+			.collect();
+		match found.as_slice() {
+			[] => Err(SignatureError::NotFound),
+			[address] => Ok(address + self.offset),
+			_ => Err(SignatureError::Ambiguous(found)),
+		}
+	}
+}
+
+impl PeImage {
+	/* Where the byte at a file offset ends up once the game is loaded */
+	pub fn address_of(&self, offset: usize) -> Option<i64> {
+		if offset < self.headers_size as usize {
+			return Some(self.image_base as i64 + offset as i64);
+		}
+		self.sections
+			.iter()
+			.find(|section| {
+				let start = section.raw_offset as usize;
+				offset >= start && offset - start < section.raw_size as usize
+			})
+			.map(|section| {
+				self.image_base as i64
+					+ section.virtual_address as i64
+					+ (offset - section.raw_offset as usize) as i64
+			})
+	}
+}
+
+impl InternalPatch {
+	/* None while a signature has not been found */
+	pub fn target(&self) -> Option<i64> {
+		match &self.signature {
+			Some(signature) => match signature.resolved {
+				Some(Ok(_)) => Some(self.address),
+				_ => None,
+			},
+			None => Some(self.address),
+		}
+	}
+
+	/* The address follows the signature when it is found */
+	pub fn resolve_signature(&mut self, image: Result<&PeImage, &PeError>) {
+		if let Some(signature) = &mut self.signature {
+			signature.resolve(image);
+			if let Some(Ok(address)) = signature.resolved {
+				self.address = address;
+			}
+		}
+	}
+}
+
+/* Sets the address of every entry with a signature, errors are reported on that entry */
+pub fn resolve_signatures(
+	patches: &mut [Patch],
+	image: Result<&PeImage, &PeError>,
+) -> Vec<Problem> {
+	let mut problems = vec![];
+	for patch in patches {
+		for (i, entry) in patch.patches.iter_mut().enumerate() {
+			entry.resolve_signature(image);
+			if let Some(AddressSignature {
+				resolved: Some(Err(err)),
+				..
+			}) = &entry.signature
+			{
+				problems.push(Problem {
+					file: patch.file.clone(),
+					key: format!("patch[{}].signature", entry.table.unwrap_or(i)),
+					line_col: None,
+					kind: ProblemKind::Signature(err.to_string()),
+				});
+			}
+		}
+	}
+	problems
+}
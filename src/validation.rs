@@ -0,0 +1,148 @@
+use crate::Config;
+
+/* Written as the FPS limit to turn the limit off */
+pub const FPS_UNLIMITED: i32 = -1;
+
+/* Range the game accepts for an integer in config.toml */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IntRule {
+	pub key: &'static str,
+	pub min: i32,
+	pub max: i32,
+	/* A value outside the range with its own meaning and a name for it */
+	pub special: Option<(i32, &'static str)>,
+}
+
+impl IntRule {
+	pub fn check(&self, value: i32) -> Result<(), String> {
+		if (self.min..=self.max).contains(&value)
+			|| self.special.map(|(special, _)| special) == Some(value)
+		{
+			return Ok(());
+		}
+		Err(match self.special {
+			Some((special, name)) => format!(
+				"must be between {} and {}, or {} for {}",
+				self.min, self.max, special, name
+			),
+			None => format!("must be between {} and {}", self.min, self.max),
+		})
+	}
+}
+
+pub const FPS: IntRule = IntRule {
+	key: "fps",
+	min: 1,
+	max: 1000,
+	special: Some((FPS_UNLIMITED, "unlimited")),
+};
+
+pub const INTERNAL_RES_X: IntRule = IntRule {
+	key: "internalRes.x",
+	min: 320,
+	max: 7680,
+	special: None,
+};
+
+pub const INTERNAL_RES_Y: IntRule = IntRule {
+	key: "internalRes.y",
+	min: 180,
+	max: 4320,
+	special: None,
+};
+
+pub const RUMBLE_INTENSITY: IntRule = IntRule {
+	key: "rumbleIntensity",
+	min: 0,
+	max: 100,
+	special: None,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ConfigError {
+	pub key: &'static str,
+	pub message: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Axis {
+	X,
+	Y,
+}
+
+/* Reduced width:height, e.g. 16:9 */
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AspectRatio {
+	pub width: i32,
+	pub height: i32,
+}
+
+impl AspectRatio {
+	pub fn of(x: i32, y: i32) -> Option<AspectRatio> {
+		if x <= 0 || y <= 0 {
+			return None;
+		}
+		let gcd = num::integer::gcd(x, y);
+		Some(AspectRatio {
+			width: x / gcd,
+			height: y / gcd,
+		})
+	}
+
+	/* Rounded to the nearest pixel */
+	pub fn other_axis(self, axis: Axis, value: i32) -> i32 {
+		let (from, to) = match axis {
+			Axis::X => (self.width as i64, self.height as i64),
+			Axis::Y => (self.height as i64, self.width as i64),
+		};
+		((value as i64 * to + from / 2) / from) as i32
+	}
+}
+
+impl Config {
+	pub fn validate(&self) -> Vec<ConfigError> {
+		[
+			(FPS, self.fps),
+			(INTERNAL_RES_X, self.internal_res_x),
+			(INTERNAL_RES_Y, self.internal_res_y),
+			(RUMBLE_INTENSITY, self.rumble_intensity),
+		]
+		.into_iter()
+		.filter_map(|(rule, value)| {
+			rule.check(value).err().map(|message| ConfigError {
+				key: rule.key,
+				message,
+			})
+		})
+		.collect()
+	}
+
+	/* With a locked aspect ratio the other axis follows */
+	pub fn set_resolution(
+		&mut self,
+		axis: Axis,
+		value: i32,
+		lock: Option<AspectRatio>,
+	) -> Result<(), String> {
+		let (x, y) = match (axis, lock) {
+			(Axis::X, Some(ratio)) => (value, ratio.other_axis(axis, value)),
+			(Axis::Y, Some(ratio)) => (ratio.other_axis(axis, value), value),
+			(Axis::X, None) => (value, self.internal_res_y),
+			(Axis::Y, None) => (self.internal_res_x, value),
+		};
+		self.set_internal_res(x, y)
+	}
+
+	/* Nothing changes if either axis would end up out of range */
+	pub fn set_internal_res(&mut self, x: i32, y: i32) -> Result<(), String> {
+		INTERNAL_RES_X
+			.check(x)
+			.map_err(|err| format!("width {}", err))?;
+		INTERNAL_RES_Y
+			.check(y)
+			.map_err(|err| format!("height {}", err))?;
+		self.internal_res_x = x;
+		self.internal_res_y = y;
+		Ok(())
+	}
+}
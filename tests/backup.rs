@@ -0,0 +1,102 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	backup::{self, DiffLine, BACKUP_COUNT},
+	AllConfig,
+};
+
+#[test]
+fn saving_keeps_backups() {
+	let (source, dir) = fixture("basic", "backups");
+	let (mut config, _) = AllConfig::load(&dir);
+	assert!(backup::backups(&dir, "config.toml").is_empty());
+
+	for fps in 0..BACKUP_COUNT + 2 {
+		config.config.fps = fps as i32;
+		config.save(&dir).unwrap();
+	}
+	let backups = backup::backups(&dir, "config.toml");
+	assert_eq!(backups.len(), BACKUP_COUNT);
+	assert_eq!(backups[0].index, 1);
+	let newest = std::fs::read_to_string(&backups[0].path).unwrap();
+	assert!(newest.contains(&format!("fps = {}\n", BACKUP_COUNT)));
+
+	/* Untouched files are not rewritten and get no backup */
+	assert!(backup::backups(&dir, "keyconfig.toml").is_empty());
+	assert!(!dir.join("patches/.nocredits.toml.tmp").exists());
+
+	config.patches[0].enabled = false;
+	config.save(&dir).unwrap();
+	let backups = backup::backups(&dir, "patches/nocredits.toml");
+	assert_eq!(
+		std::fs::read(&backups[0].path).unwrap(),
+		std::fs::read(source.join("patches/nocredits.toml")).unwrap()
+	);
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn restoring_a_backup() {
+	let (source, dir) = fixture("basic", "restore");
+	let (mut config, _) = AllConfig::load(&dir);
+	config.config.fps = 30;
+	config.save(&dir).unwrap();
+
+	let backups = backup::backups(&dir, "config.toml");
+	backup::restore_backup(&dir, "config.toml", &backups[0]).unwrap();
+	assert_eq!(
+		std::fs::read(dir.join("config.toml")).unwrap(),
+		std::fs::read(source.join("config.toml")).unwrap()
+	);
+
+	/* The restore itself can be undone */
+	let backups = backup::backups(&dir, "config.toml");
+	assert_eq!(backups.len(), 2);
+	let newest = std::fs::read_to_string(&backups[0].path).unwrap();
+	assert!(newest.contains("fps = 30\n"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn diff_lines() {
+	let diff = backup::line_diff("a\nb\nc\n", "a\nc\nd\n");
+	assert_eq!(
+		diff,
+		vec![
+			DiffLine::Same("a".to_string()),
+			DiffLine::Removed("b".to_string()),
+			DiffLine::Same("c".to_string()),
+			DiffLine::Added("d".to_string()),
+		]
+	);
+}
+
+#[test]
+fn removing_and_renaming_files() {
+	let (source, dir) = fixture("basic", "remove");
+	backup::rename_file(&dir, "patches/nocredits.toml", "patches/credits.toml").unwrap();
+	assert!(!dir.join("patches/nocredits.toml").exists());
+	assert!(backup::rename_file(&dir, "config.toml", "keyconfig.toml").is_err());
+
+	backup::remove_file(&dir, "patches/credits.toml").unwrap();
+	let (config, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert!(config.patches.is_empty());
+	let backups = backup::backups(&dir, "patches/credits.toml");
+	assert_eq!(
+		std::fs::read(&backups[0].path).unwrap(),
+		std::fs::read(source.join("patches/nocredits.toml")).unwrap()
+	);
+
+	/* The deleted file is still listed and can be brought back */
+	assert_eq!(
+		backup::backed_up_files(&dir),
+		vec!["patches/credits.toml".to_string()]
+	);
+	backup::restore_backup(&dir, "patches/credits.toml", &backups[0]).unwrap();
+	let (config, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(config.patches[0].file, "patches/credits.toml");
+	std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,49 @@
+use launcher_egui::{buttons::ButtonCategory, Buttons};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+#[test]
+fn every_button_is_in_one_category() {
+	let count: usize = ButtonCategory::ALL
+		.iter()
+		.map(|category| category.buttons().count())
+		.sum();
+	assert_eq!(count, Buttons::iter().count());
+	assert_eq!(
+		Buttons::SDL_LSTICK_UP.category(),
+		ButtonCategory::Controller
+	);
+	assert_eq!(Buttons::MOUSE_X1.category(), ButtonCategory::Mouse);
+	assert_eq!(Buttons::DIVIDE.category(), ButtonCategory::Numpad);
+	assert_eq!(Buttons::NUM4.category(), ButtonCategory::Keyboard);
+	assert_eq!(Buttons::GRAVE.category(), ButtonCategory::Keyboard);
+}
+
+#[test]
+fn labels_are_unique_and_readable() {
+	for (i, button) in Buttons::iter().enumerate() {
+		for other in Buttons::iter().skip(i + 1) {
+			assert_ne!(button.label(), other.label(), "{:?} {:?}", button, other);
+		}
+	}
+	assert_eq!(Buttons::SDL_LSTICK_UP.label(), "Left Stick Up");
+	assert_eq!(Buttons::NUMPAD7.label(), "Numpad 7");
+}
+
+#[test]
+fn keyconfig_names_are_unchanged() {
+	for (name, button) in [
+		("F1", Buttons::F1),
+		("NUM0", Buttons::NUM0),
+		("UPARROW", Buttons::UPARROW),
+		("CONTROL", Buttons::CONTROL),
+		("SDL_LSTICK_UP", Buttons::SDL_LSTICK_UP),
+		("ESCAPE", Buttons::ESCAPE),
+		("NUMPAD0", Buttons::NUMPAD0),
+		("MOUSE_LEFT", Buttons::MOUSE_LEFT),
+	] {
+		assert_eq!(Buttons::from_str(name), Ok(button));
+		let written: &'static str = button.into();
+		assert_eq!(written, name);
+	}
+}
@@ -0,0 +1,48 @@
+#![allow(dead_code)]
+use std::path::{Path, PathBuf};
+
+pub fn copy_dir(from: &Path, to: &Path) {
+	std::fs::create_dir_all(to).unwrap();
+	for entry in std::fs::read_dir(from).unwrap() {
+		let entry = entry.unwrap();
+		let target = to.join(entry.file_name());
+		if entry.path().is_dir() {
+			copy_dir(&entry.path(), &target);
+		} else {
+			std::fs::copy(entry.path(), target).unwrap();
+		}
+	}
+}
+
+pub fn fixture(name: &str, test: &str) -> (PathBuf, PathBuf) {
+	let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests")
+		.join("fixtures")
+		.join(name);
+	let dir = std::env::temp_dir().join(format!(
+		"launcher_egui_{}_{}_{}",
+		test,
+		name,
+		std::process::id()
+	));
+	let _ = std::fs::remove_dir_all(&dir);
+	copy_dir(&source, &dir);
+	(source, dir)
+}
+
+pub fn assert_same_files(expected: &Path, actual: &Path) {
+	for entry in std::fs::read_dir(expected).unwrap() {
+		let entry = entry.unwrap();
+		let other = actual.join(entry.file_name());
+		if entry.path().is_dir() {
+			assert_same_files(&entry.path(), &other);
+		} else {
+			assert_eq!(
+				std::fs::read(entry.path()).unwrap(),
+				std::fs::read(&other).unwrap(),
+				"{} changed",
+				other.display()
+			);
+		}
+	}
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	compat::{compatibility, Compatibility, ExeHash, GameInfo, Version},
+	pe::{PeImage, GAME_EXE},
+	problems::ProblemKind,
+	AllConfig, Patch,
+};
+use std::path::Path;
+
+const FIXTURE_SHA1: &str = "e44f065789c97b7b8b2980f2f940eac5cf4f0a7b";
+
+fn fixture_exe() -> PeImage {
+	let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests")
+		.join("fixtures")
+		.join("executable")
+		.join(GAME_EXE);
+	PeImage::load(&path).unwrap()
+}
+
+fn version(text: &str) -> Version {
+	text.parse().unwrap()
+}
+
+#[test]
+fn versions_compare_by_number() {
+	assert_eq!(version("1.0"), version("1.0.0"));
+	assert!(version("1.10") > version("1.2"));
+	assert!(version("0.9.9") < version("1"));
+	assert_eq!(version(" 1.02 ").to_string(), "1.2");
+	assert!("1.x".parse::<Version>().is_err());
+	assert!("".parse::<Version>().is_err());
+
+	assert_eq!(
+		"E44F065789C97B7B8B2980F2F940EAC5CF4F0A7B".parse(),
+		Ok(ExeHash(FIXTURE_SHA1.to_string()))
+	);
+	assert!("e44f0657".parse::<ExeHash>().is_err());
+}
+
+#[test]
+fn executable_hash_and_version() {
+	let image = fixture_exe();
+	let info = GameInfo::of(&image);
+	assert_eq!(info.hash.0, FIXTURE_SHA1);
+	assert_eq!(info.version, None);
+
+	/* A VS_FIXEDFILEINFO for 1.2.3.4 in .data */
+	let mut data = image.data.clone();
+	let mut fixed = vec![0xBD, 0x04, 0xEF, 0xFE, 0x00, 0x00, 0x01, 0x00];
+	fixed.extend([0x02, 0x00, 0x01, 0x00, 0x04, 0x00, 0x03, 0x00]);
+	data[0x700..0x710].copy_from_slice(&fixed);
+	let versioned = PeImage::parse(data).unwrap();
+	assert_eq!(versioned.file_version(), Some(version("1.2.3.4")));
+}
+
+#[test]
+fn patches_are_checked_against_the_game() {
+	let game = GameInfo {
+		hash: ExeHash(FIXTURE_SHA1.to_string()),
+		version: Some(version("1.0.3")),
+	};
+	let mut patch = Patch::new("target");
+	assert!(matches!(
+		compatibility(&patch, Some(&game)),
+		Compatibility::Unknown(_)
+	));
+
+	patch.game_version = Some(version("1.0.3.0"));
+	assert_eq!(
+		compatibility(&patch, Some(&game)),
+		Compatibility::Compatible
+	);
+	assert!(matches!(
+		compatibility(&patch, None),
+		Compatibility::Unknown(_)
+	));
+	patch.game_version = Some(version("1.0.2"));
+	assert_eq!(
+		compatibility(&patch, Some(&game)),
+		Compatibility::Incompatible("made for game version 1.0.2, this is 1.0.3".to_string())
+	);
+
+	/* The hash decides when both are given */
+	patch.game_sha1 = Some(ExeHash(FIXTURE_SHA1.to_string()));
+	assert_eq!(
+		compatibility(&patch, Some(&game)),
+		Compatibility::Compatible
+	);
+	patch.game_sha1 = Some(ExeHash("0".repeat(40)));
+	patch.game_version = None;
+	assert!(matches!(
+		compatibility(&patch, Some(&game)),
+		Compatibility::Incompatible(_)
+	));
+
+	patch.game_sha1 = None;
+	patch.min_launcher_version = Some(Version::launcher());
+	assert!(matches!(
+		compatibility(&patch, Some(&game)),
+		Compatibility::Unknown(_)
+	));
+	patch.min_launcher_version = Some(version("9999"));
+	assert!(matches!(
+		compatibility(&patch, Some(&game)),
+		Compatibility::Incompatible(reason) if reason.starts_with("needs launcher 9999 or newer")
+	));
+}
+
+#[test]
+fn patch_metadata_round_trips() {
+	let (_, dir) = fixture("basic", "compat");
+	let path = dir.join("patches").join("nocredits.toml");
+	let text = std::fs::read_to_string(&path).unwrap();
+	std::fs::write(
+		&path,
+		text.replace(
+			"author = \"someone\"\n",
+			&format!(
+				"author = \"someone\"\nversion = \"2.1\"\ndescription = \"Skips the credit screen\"\nurl = \"https://example.com/nocredits\"\ngame_sha1 = \"{}\"\nmin_launcher_version = \"0.1\"\n",
+				FIXTURE_SHA1.to_uppercase()
+			),
+		),
+	)
+	.unwrap();
+
+	let (mut all, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	let patch = &all.patches[0];
+	assert_eq!(patch.version, "2.1");
+	assert_eq!(patch.description, "Skips the credit screen");
+	assert_eq!(patch.url, "https://example.com/nocredits");
+	assert_eq!(patch.game_sha1, Some(ExeHash(FIXTURE_SHA1.to_string())));
+	assert_eq!(patch.game_version, None);
+	assert_eq!(patch.min_launcher_version, Some(version("0.1")));
+	let game = GameInfo::of(&fixture_exe());
+	assert_eq!(compatibility(patch, Some(&game)), Compatibility::Compatible);
+
+	all.patches[0].url.clear();
+	all.patches[0].game_sha1 = None;
+	all.patches[0].game_version = Some(version("1.0.3"));
+	all.save(&dir).unwrap();
+	let saved = std::fs::read_to_string(&path).unwrap();
+	assert!(!saved.contains("url"));
+	assert!(!saved.contains("game_sha1"));
+	assert!(saved.contains("game_version = \"1.0.3\""));
+	assert!(saved.contains("description = \"Skips the credit screen\""));
+	let (reloaded, _) = AllConfig::load(&dir);
+	assert_eq!(reloaded, all);
+
+	std::fs::write(
+		&path,
+		saved.replace("game_version = \"1.0.3\"", "game_version = \"latest\""),
+	)
+	.unwrap();
+	let (_, problems) = AllConfig::load(&dir);
+	assert_eq!(problems.len(), 1);
+	assert_eq!(problems[0].key, "game_version");
+	assert!(matches!(problems[0].kind, ProblemKind::InvalidValue(_)));
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	conflicts::{can_share, find_conflicts, Conflict},
+	AllConfig, Buttons, Keyconfig,
+};
+
+#[test]
+fn sections_that_can_share() {
+	/* Gameplay and the free camera are never active together */
+	assert!(can_share("TRIANGLE", "CAMERA_MOVE_FORWARD"));
+	assert!(!can_share("TRIANGLE", "SQUARE"));
+	assert!(!can_share("CAMERA_ZOOM_IN", "CAMERA_ZOOM_OUT"));
+	/* Game state buttons and the toggle work everywhere */
+	assert!(!can_share("TEST", "CAMERA_MOVE_UP"));
+	assert!(!can_share("SERVICE", "START"));
+	assert!(!can_share("CAMERA_UNLOCK_TOGGLE", "START"));
+	assert!(!can_share("CAMERA_UNLOCK_TOGGLE", "CAMERA_MOVE_UP"));
+	/* Except with each other, as the game ships them */
+	assert!(can_share("ADVERTISE", "CAMERA_UNLOCK_TOGGLE"));
+	assert!(can_share("CAMERA_UNLOCK_TOGGLE", "TEST"));
+}
+
+#[test]
+fn duplicates_and_shared_buttons() {
+	let mut keyconfig = Keyconfig::default();
+	keyconfig.set(
+		"TRIANGLE",
+		vec![Buttons::W, Buttons::SDL_Y, Buttons::W, Buttons::W],
+	);
+	keyconfig.set("SQUARE", vec![Buttons::SDL_Y]);
+	keyconfig.set("CAMERA_MOVE_FORWARD", vec![Buttons::W]);
+	keyconfig.set("TEST", vec![Buttons::F1]);
+	keyconfig.set("CAMERA_MOVE_UP", vec![Buttons::F1]);
+	let conflicts = find_conflicts(&keyconfig);
+	assert_eq!(
+		conflicts,
+		vec![
+			Conflict::Shared {
+				button: Buttons::F1,
+				first: "TEST".to_string(),
+				second: "CAMERA_MOVE_UP".to_string(),
+			},
+			Conflict::Shared {
+				button: Buttons::SDL_Y,
+				first: "TRIANGLE".to_string(),
+				second: "SQUARE".to_string(),
+			},
+			Conflict::Duplicate {
+				action: "TRIANGLE".to_string(),
+				button: Buttons::W,
+			},
+		]
+	);
+	assert_eq!(
+		conflicts[0].describe("CAMERA_MOVE_UP"),
+		"also bound to TEST"
+	);
+	assert_eq!(
+		conflicts[1].to_string(),
+		"SDL_Y is bound to both TRIANGLE and SQUARE"
+	);
+	assert!(conflicts[2].involves("TRIANGLE") && !conflicts[2].involves("SQUARE"));
+}
+
+#[test]
+fn stock_bindings_have_no_conflicts() {
+	assert_eq!(find_conflicts(Keyconfig::game_defaults()), vec![]);
+	let (_, dir) = fixture("basic", "conflicts");
+	let (config, _) = AllConfig::load(&dir);
+	assert_eq!(find_conflicts(&config.keyconfig), vec![]);
+	std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	actions::ActionGroup,
+	gamepad::is_controller_button,
+	preset,
+	problems::{has_problems, ProblemKind},
+	schema::ConfigValue,
+	AllConfig, Buttons, Config, Keyconfig,
+};
+
+#[test]
+fn reset_restores_defaults() {
+	let defaults = Config::game_defaults();
+	assert_eq!(defaults.fps, 60);
+	assert_eq!(
+		(defaults.internal_res_x, defaults.internal_res_y),
+		(1920, 1080)
+	);
+	assert!(defaults.validate().is_empty());
+
+	let mut config = Config {
+		fps: 144,
+		internal_res_x: 1280,
+		internal_res_y: 720,
+		fullscreen: true,
+		rumble_intensity: 10,
+		other: vec![(vec!["vsync".to_string()], ConfigValue::Bool(true))],
+	};
+	assert!(!config.is_default("fps"));
+	config.reset("fps");
+	assert!(config.is_default("fps"));
+	assert!(!config.is_default("fullscreen"));
+	/* Unknown keys have no default */
+	assert!(config.is_default("vsync"));
+
+	config.reset_group("Controller");
+	assert_eq!(config.rumble_intensity, 100);
+	assert!(config.fullscreen);
+
+	config.reset_all();
+	assert_eq!(
+		config,
+		Config {
+			other: vec![(vec!["vsync".to_string()], ConfigValue::Bool(true))],
+			..Config::game_defaults()
+		}
+	);
+
+	let mut keyconfig = Keyconfig::game_defaults().clone();
+	let bundled = preset::bundled()
+		.into_iter()
+		.find(|preset| preset.name == "Default keyboard")
+		.unwrap();
+	/* The defaults add controller buttons to the keyboard preset */
+	for (action, buttons) in &bundled.bindings {
+		let keyboard: Vec<Buttons> = keyconfig
+			.get(action)
+			.unwrap()
+			.iter()
+			.copied()
+			.filter(|button| !is_controller_button(*button))
+			.collect();
+		assert_eq!(&keyboard, buttons, "{}", action);
+	}
+	assert_eq!(
+		keyconfig.get("START"),
+		Some(&vec![Buttons::ENTER, Buttons::SDL_START])
+	);
+	assert_eq!(Keyconfig::default_for("PHOTO_MODE"), None);
+
+	keyconfig.set("START", vec![Buttons::SDL_START]);
+	keyconfig.set("CAMERA_MOVE_UP", vec![]);
+	keyconfig.set("PHOTO_MODE", vec![Buttons::P]);
+	keyconfig.reset("START");
+	assert_eq!(
+		keyconfig.get("START"),
+		Some(&vec![Buttons::ENTER, Buttons::SDL_START])
+	);
+	keyconfig.reset_group(ActionGroup::Gameplay);
+	assert_eq!(keyconfig.get("CAMERA_MOVE_UP"), Some(&vec![]));
+	keyconfig.reset_all();
+	assert_eq!(keyconfig.get("CAMERA_MOVE_UP"), Some(&vec![Buttons::SPACE]));
+	assert_eq!(keyconfig.get("PHOTO_MODE"), Some(&vec![Buttons::P]));
+}
+
+#[test]
+fn missing_files_are_created_from_defaults() {
+	let (_, dir) = fixture("basic", "defaults_missing");
+	let config = std::fs::read_to_string(dir.join("config.toml")).unwrap();
+	std::fs::remove_file(dir.join("keyconfig.toml")).unwrap();
+
+	assert_eq!(AllConfig::create_missing(&dir), Ok(vec!["keyconfig.toml"]));
+	assert_eq!(
+		std::fs::read_to_string(dir.join("config.toml")).unwrap(),
+		config
+	);
+	let (loaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(loaded.keyconfig, *Keyconfig::game_defaults());
+
+	std::fs::remove_file(dir.join("config.toml")).unwrap();
+	assert_eq!(AllConfig::create_missing(&dir), Ok(vec!["config.toml"]));
+	let (loaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(loaded.config, Config::game_defaults());
+	assert_eq!(AllConfig::create_missing(&dir), Ok(vec![]));
+}
+
+#[test]
+fn missing_keys_are_filled_in_and_saved() {
+	let (_, dir) = fixture("basic", "defaults_filled_in");
+	let config_path = dir.join("config.toml");
+	let keyconfig_path = dir.join("keyconfig.toml");
+	let config = std::fs::read_to_string(&config_path).unwrap();
+	std::fs::write(&config_path, config.replace("fps = 60\n", "")).unwrap();
+	let keyconfig = std::fs::read_to_string(&keyconfig_path).unwrap();
+	std::fs::write(
+		&keyconfig_path,
+		keyconfig.replace("CAMERA_MOVE_SLOW = [\"TAB\"]\n", ""),
+	)
+	.unwrap();
+
+	let (mut all, problems) = AllConfig::load(&dir);
+	let kinds: Vec<(&str, &ProblemKind)> = problems
+		.iter()
+		.map(|problem| (problem.key.as_str(), &problem.kind))
+		.collect();
+	assert_eq!(
+		kinds,
+		[
+			("fps", &ProblemKind::FilledIn("60".to_string())),
+			(
+				"CAMERA_MOVE_SLOW",
+				&ProblemKind::FilledIn(
+					Keyconfig::default_for("CAMERA_MOVE_SLOW")
+						.unwrap()
+						.iter()
+						.map(|button| -> &'static str { button.into() })
+						.collect::<Vec<_>>()
+						.join(",")
+				)
+			),
+		]
+	);
+	assert!(!has_problems(&problems, "config.toml"));
+	assert_eq!(all.config.fps, 60);
+	assert_eq!(
+		all.keyconfig.get("CAMERA_MOVE_SLOW"),
+		Keyconfig::default_for("CAMERA_MOVE_SLOW").as_ref()
+	);
+
+	all.config.reset_all();
+	all.keyconfig.reset_all();
+	all.save(&dir).unwrap();
+	assert!(std::fs::read_to_string(&config_path)
+		.unwrap()
+		.contains("fps = 60"));
+	assert!(std::fs::read_to_string(&keyconfig_path)
+		.unwrap()
+		.contains("CAMERA_MOVE_SLOW = "));
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(reloaded, all);
+}
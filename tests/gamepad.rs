@@ -0,0 +1,80 @@
+use launcher_egui::{
+	gamepad::{is_controller_button, Gamepad, GamepadEvent, GamepadState, VirtualGamepad},
+	Buttons, Keyconfig,
+};
+
+#[test]
+fn script_plays_back_over_polls() {
+	let mut gamepad = VirtualGamepad::from_script(
+		"# hold A then tap B
+press SDL_A
+wait 2
+press SDL_B
+release SDL_B # same poll
+wait 1
+release SDL_A",
+	)
+	.unwrap();
+	assert_eq!(gamepad.poll(), vec![GamepadEvent::Pressed(Buttons::SDL_A)]);
+	assert_eq!(gamepad.poll(), vec![]);
+	assert_eq!(
+		gamepad.poll(),
+		vec![
+			GamepadEvent::Pressed(Buttons::SDL_B),
+			GamepadEvent::Released(Buttons::SDL_B)
+		]
+	);
+	assert!(gamepad.pending());
+	assert_eq!(gamepad.poll(), vec![GamepadEvent::Released(Buttons::SDL_A)]);
+	assert!(!gamepad.pending());
+	assert_eq!(gamepad.poll(), vec![]);
+}
+
+#[test]
+fn script_errors() {
+	let error = |script| VirtualGamepad::from_script(script).err().unwrap();
+	assert_eq!(
+		error("press SDL_A\npress SDL_Q"),
+		"line 2: unknown button SDL_Q"
+	);
+	assert_eq!(error("wait soon"), "line 1: wait needs a number of polls");
+	assert_eq!(error("hold SDL_A"), "line 1: unknown command hold");
+}
+
+#[test]
+fn press_to_bind_and_test_panel() {
+	let mut gamepad = VirtualGamepad::new();
+	let mut state = GamepadState::default();
+	let mut keyconfig = Keyconfig::default();
+	keyconfig.set("CROSS", vec![Buttons::S, Buttons::SDL_A]);
+	keyconfig.set("CAMERA_MOVE_DOWN", vec![Buttons::SDL_A]);
+	keyconfig.set("START", vec![Buttons::ENTER]);
+
+	/* The first new press is what gets bound */
+	gamepad.press(Buttons::SDL_START);
+	let pressed = state.update(&gamepad.poll());
+	assert_eq!(pressed, vec![Buttons::SDL_START]);
+	keyconfig.get_mut("START").unwrap().push(pressed[0]);
+	assert_eq!(state.active_actions(&keyconfig), vec!["START"]);
+
+	/* Held buttons are not reported again */
+	gamepad.press(Buttons::SDL_START);
+	gamepad.press(Buttons::SDL_A);
+	assert_eq!(state.update(&gamepad.poll()), vec![Buttons::SDL_A]);
+	assert_eq!(
+		state.active_actions(&keyconfig),
+		vec!["START", "CROSS", "CAMERA_MOVE_DOWN"]
+	);
+
+	gamepad.release(Buttons::SDL_START);
+	gamepad.release(Buttons::SDL_A);
+	state.update(&gamepad.poll());
+	assert!(state.held.is_empty());
+	assert!(state.active_actions(&keyconfig).is_empty());
+}
+
+#[test]
+fn controller_buttons() {
+	assert!(is_controller_button(Buttons::SDL_RSTICK_PRESS));
+	assert!(!is_controller_button(Buttons::S));
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "gui")]
+
+use eframe::egui::{Key, Modifiers};
+use launcher_egui::{
+	keymap::{button_for_key, button_for_modifiers, KEY_TABLE},
+	Buttons,
+};
+use std::str::FromStr;
+
+#[test]
+fn key_table_is_one_to_one() {
+	for (i, (key, button)) in KEY_TABLE.iter().enumerate() {
+		for (other_key, other_button) in &KEY_TABLE[i + 1..] {
+			assert_ne!(key, other_key);
+			assert_ne!(button, other_button);
+		}
+	}
+}
+
+#[test]
+fn keys_map_to_the_same_name() {
+	/* Letters, arrows and navigation keys use the same name on both sides */
+	for (key, button) in KEY_TABLE {
+		let key_name = format!("{:?}", key);
+		let button_name: &'static str = button.into();
+		let expected = match key_name.strip_prefix("Arrow") {
+			Some(direction) => format!("{}ARROW", direction.to_uppercase()),
+			None => key_name.to_uppercase(),
+		};
+		assert_eq!(button_name, expected);
+		assert_eq!(Buttons::from_str(button_name), Ok(*button));
+	}
+	assert_eq!(button_for_key(Key::Num7), Some(Buttons::NUM7));
+	assert_eq!(button_for_key(Key::ArrowLeft), Some(Buttons::LEFTARROW));
+}
+
+#[test]
+fn escape_is_not_mapped() {
+	/* It cancels binding instead */
+	assert_eq!(button_for_key(Key::Escape), None);
+	assert_eq!(button_for_key(Key::PageDown), Some(Buttons::PAGEDOWN));
+}
+
+#[test]
+fn modifiers() {
+	let none = Modifiers::default();
+	let ctrl = Modifiers {
+		ctrl: true,
+		..Default::default()
+	};
+	let shift = Modifiers {
+		shift: true,
+		..Default::default()
+	};
+	assert_eq!(button_for_modifiers(none, ctrl), Some(Buttons::CONTROL));
+	assert_eq!(button_for_modifiers(none, shift), Some(Buttons::SHIFT));
+	let alt = Modifiers {
+		alt: true,
+		..Default::default()
+	};
+	assert_eq!(button_for_modifiers(none, alt), Some(Buttons::ALT));
+	/* Held before capturing started, e.g. while clicking the button */
+	assert_eq!(button_for_modifiers(ctrl, ctrl), None);
+	assert_eq!(button_for_modifiers(ctrl, none), None);
+}
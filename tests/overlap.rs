@@ -0,0 +1,111 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	overlap::{find_overlaps, Overlap},
+	AllConfig, DataTypes, InternalPatch, Patch,
+};
+
+fn entry(address: i64, data_type: DataTypes) -> InternalPatch {
+	InternalPatch {
+		address,
+		data_type,
+		..Default::default()
+	}
+}
+
+fn patch(file: &str, enabled: bool, patches: Vec<InternalPatch>) -> Patch {
+	Patch {
+		name: file.to_string(),
+		enabled,
+		patches,
+		file: file.to_string(),
+		..Default::default()
+	}
+}
+
+#[test]
+fn spans_follow_data_type() {
+	assert_eq!(entry(0x100, DataTypes::u8).span(), 0x100..0x101);
+	assert_eq!(entry(0x100, DataTypes::i32).span(), 0x100..0x104);
+	assert_eq!(entry(0x100, DataTypes::i64).span(), 0x100..0x108);
+	assert_eq!(entry(0x100, DataTypes::u16_arr).span(), 0x100..0x100);
+	let array = InternalPatch {
+		data_int_arr: vec![1, 2, 3],
+		..entry(0x100, DataTypes::u16_arr)
+	};
+	assert_eq!(array.span(), 0x100..0x106);
+	let string = InternalPatch {
+		data_string: "FREE PLAY".to_string(),
+		..entry(0x100, DataTypes::string)
+	};
+	assert_eq!(string.byte_len(), 9);
+}
+
+#[test]
+fn overlapping_enabled_patches_are_reported() {
+	let patches = vec![
+		patch(
+			"patches/a.toml",
+			true,
+			vec![entry(0x1000, DataTypes::i32), entry(0x1001, DataTypes::u8)],
+		),
+		patch(
+			"patches/b.toml",
+			true,
+			vec![
+				InternalPatch {
+					data_int_arr: vec![0, 0],
+					..entry(0x1002, DataTypes::u16_arr)
+				},
+				/* Right after a.toml's i32 */
+				entry(0x1004, DataTypes::u8),
+			],
+		),
+		patch("patches/c.toml", false, vec![entry(0x1000, DataTypes::i64)]),
+	];
+	let overlaps = find_overlaps(&patches);
+	assert_eq!(
+		overlaps,
+		vec![Overlap {
+			first_file: "patches/a.toml".to_string(),
+			first_address: 0x1000,
+			second_file: "patches/b.toml".to_string(),
+			second_address: 0x1002,
+			bytes: 0x1002..0x1004,
+		}]
+	);
+	let overlap = &overlaps[0];
+	assert!(overlap.involves_entry("patches/b.toml", 0x1002));
+	assert!(!overlap.involves_entry("patches/a.toml", 0x1001));
+	assert_eq!(
+		overlap.describe("patches/b.toml"),
+		"0x1002 overlaps patches/a.toml at 0x1000"
+	);
+	assert_eq!(
+		overlap.to_string(),
+		"patches/a.toml at 0x1000 and patches/b.toml at 0x1002 both write 0x1002..0x1004"
+	);
+}
+
+#[test]
+fn overlaps_between_patch_files() {
+	let (_, dir) = fixture("basic", "overlap_files");
+	std::fs::write(
+		dir.join("patches").join("freeplay.toml"),
+		"name = \"Free play\"\nauthor = \"someone else\"\nenabled = true\n\n[[patch]]\naddress = 0x140000424\ndata_type = \"u8\"\ndata = 0\n",
+	)
+	.unwrap();
+	let (mut all, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	let overlaps = find_overlaps(&all.patches);
+	assert_eq!(overlaps.len(), 1);
+	assert!(overlaps[0].involves("patches/nocredits.toml"));
+	assert!(overlaps[0].involves("patches/freeplay.toml"));
+	assert_eq!(overlaps[0].bytes, 0x140000424..0x140000425);
+
+	for patch in &mut all.patches {
+		patch.enabled = patch.file != "patches/freeplay.toml";
+	}
+	assert_eq!(find_overlaps(&all.patches), vec![]);
+}
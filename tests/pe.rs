@@ -0,0 +1,263 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	pe::{change_report, compare, hex_bytes, ApplyError, Change, PeError, PeImage, GAME_EXE},
+	problems::ProblemKind,
+	AllConfig, DataTypes, InternalPatch, Patch,
+};
+use std::path::Path;
+
+fn fixture_exe() -> PeImage {
+	let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests")
+		.join("fixtures")
+		.join("executable")
+		.join(GAME_EXE);
+	PeImage::load(&path).unwrap()
+}
+
+#[test]
+fn addresses_map_to_file_offsets() {
+	let image = fixture_exe();
+	assert_eq!(image.image_base, 0x140000000);
+	let names: Vec<&str> = image
+		.sections
+		.iter()
+		.map(|section| section.name.as_str())
+		.collect();
+	assert_eq!(names, [".text", ".data"]);
+
+	assert_eq!(image.file_offset(0x140000040), Ok(0x40));
+	assert_eq!(image.file_offset(0x140000400), Ok(0x400));
+	assert_eq!(image.read(0x140000400, 2), Ok(&[0x74, 0x05][..]));
+	assert_eq!(image.read(0x140000600, 13), Ok(&b"launcher-egui"[..]));
+	/* .data is bigger in memory than in the file */
+	assert_eq!(
+		image.read(0x140000800, 1),
+		Err(PeError::NotInFile(0x140000800))
+	);
+	assert_eq!(
+		image.read(0x1400007FF, 2),
+		Err(PeError::NotInFile(0x140000800))
+	);
+	assert_eq!(
+		image.file_offset(0x140000A00),
+		Err(PeError::Unmapped(0x140000A00))
+	);
+	assert_eq!(image.file_offset(0x400), Err(PeError::Unmapped(0x400)));
+
+	assert!(matches!(
+		PeImage::parse(b"MZ not really".to_vec()),
+		Err(PeError::Invalid(_))
+	));
+	assert!(matches!(
+		PeImage::parse(vec![0; 16]),
+		Err(PeError::Invalid(_))
+	));
+}
+
+#[test]
+fn patches_show_the_bytes_they_replace() {
+	let (_, dir) = fixture("basic", "pe_original");
+	let image = fixture_exe();
+	let (mut all, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	let entries = &mut all.patches[0].patches;
+
+	let nop = compare(&image, &entries[0]).unwrap();
+	assert_eq!(hex_bytes(&nop.original), "74 05");
+	assert_eq!(hex_bytes(&nop.patched), "90 90");
+	assert!(!nop.mismatch);
+	let credits = compare(&image, &entries[1]).unwrap();
+	assert_eq!(credits.original, [3, 0, 0, 0]);
+	assert_eq!(credits.patched, [5, 0, 0, 0]);
+	let text = compare(&image, &entries[2]).unwrap();
+	assert_eq!(text.original, b"CREDIT(S)");
+
+	entries[0].original = Some(vec![0x74, 0x05]);
+	entries[1].original = Some(vec![0x04, 0x00, 0x00, 0x00]);
+	assert!(!compare(&image, &entries[0]).unwrap().mismatch);
+	assert!(compare(&image, &entries[1]).unwrap().mismatch);
+
+	all.save(&dir).unwrap();
+	let text = std::fs::read_to_string(dir.join("patches/nocredits.toml")).unwrap();
+	assert!(text.contains("original = [0x74, 0x05]"));
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(reloaded, all);
+
+	all.patches[0].patches[1].original = None;
+	all.save(&dir).unwrap();
+	let text = std::fs::read_to_string(dir.join("patches/nocredits.toml")).unwrap();
+	assert_eq!(text.matches("original").count(), 1);
+
+	std::fs::write(
+		dir.join("patches/nocredits.toml"),
+		text.replace("original = [0x74, 0x05]", "original = [0x74, 0x105]"),
+	)
+	.unwrap();
+	let (_, problems) = AllConfig::load(&dir);
+	assert_eq!(problems.len(), 1);
+	assert_eq!(problems[0].key, "patch[0].original[1]");
+	assert!(matches!(problems[0].kind, ProblemKind::InvalidValue(_)));
+
+	/* Each problem has the index it has in the file */
+	std::fs::write(
+		dir.join("patches/nocredits.toml"),
+		text.replace("original = [0x74, 0x05]", "original = [1, \"x\", 300]"),
+	)
+	.unwrap();
+	let (_, problems) = AllConfig::load(&dir);
+	let found: Vec<(&str, &ProblemKind)> = problems
+		.iter()
+		.map(|problem| (problem.key.as_str(), &problem.kind))
+		.collect();
+	assert_eq!(
+		found,
+		vec![
+			(
+				"patch[0].original[1]",
+				&ProblemKind::WrongType {
+					expected: "integer",
+					found: "string"
+				}
+			),
+			(
+				"patch[0].original[2]",
+				&ProblemKind::InvalidValue("300, expected a byte".to_string())
+			),
+		]
+	);
+}
+
+#[test]
+fn patched_copy_lists_every_changed_byte() {
+	let (_, dir) = fixture("basic", "pe_apply");
+	let image = fixture_exe();
+	let (mut all, _) = AllConfig::load(&dir);
+	let mut later = Patch::new("later");
+	later.enabled = true;
+	later.patches.push(InternalPatch {
+		address: 0x140000401,
+		data_type: DataTypes::u16,
+		data_int: 0x05EB,
+		..Default::default()
+	});
+	all.patches.push(later.clone());
+	later.file = "patches/disabled.toml".to_string();
+	later.enabled = false;
+	later.patches[0].address = 0x140000200;
+	all.patches.push(later);
+
+	let (data, changes) = image.apply(&all.patches).unwrap();
+	assert_eq!(data.len(), image.data.len());
+	assert_eq!(&data[0x400..0x403], &[0x90, 0xEB, 0x05]);
+	assert_eq!(&data[0x410..0x414], &[5, 0, 0, 0]);
+	assert_eq!(&data[0x420..0x429], b"FREE PLAY");
+	assert_eq!(&data[0x200..0x202], &[0x48, 0x89]);
+	assert_eq!(changes.len(), 4);
+	assert_eq!(
+		changes[3],
+		Change {
+			file: "patches/later.toml".to_string(),
+			address: 0x140000401,
+			offset: 0x401,
+			original: vec![0x90, 0xCC],
+			patched: vec![0xEB, 0x05],
+		}
+	);
+	let report = change_report(&changes);
+	assert!(report.starts_with(
+		"patches/nocredits.toml at 0x140000400, file offset 0x400\n\t0x400: 74 -> 90\n\t0x401: 05 -> 90\n"
+	));
+	assert!(report.ends_with(
+		"patches/later.toml at 0x140000401, file offset 0x401\n\t0x401: 90 -> EB\n\t0x402: CC -> 05\n"
+	));
+
+	all.patches[1].patches[0].address = 0x1400007FF;
+	assert_eq!(
+		image.apply(&all.patches),
+		Err(vec![ApplyError {
+			file: "patches/later.toml".to_string(),
+			address: 0x1400007FF,
+			error: PeError::NotInFile(0x140000800),
+		}])
+	);
+}
+
+#[test]
+fn values_that_do_not_fit_are_reported() {
+	let image = fixture_exe();
+	let mut wide = Patch::new("wide");
+	wide.enabled = true;
+	wide.patches.push(InternalPatch {
+		address: 0x140000400,
+		data_type: DataTypes::u8,
+		data_int: 0x1FF,
+		..Default::default()
+	});
+	wide.patches.push(InternalPatch {
+		address: 0x140000410,
+		data_type: DataTypes::i8_arr,
+		data_int_arr: vec![-128, 127, 300],
+		..Default::default()
+	});
+	wide.patches.push(InternalPatch {
+		address: 0x140000420,
+		data_type: DataTypes::u32_arr,
+		data_int_arr: vec![0, 0xFFFFFFFF],
+		..Default::default()
+	});
+	assert_eq!(
+		image.apply(&[wide.clone()]),
+		Err(vec![
+			ApplyError {
+				file: wide.file.clone(),
+				address: 0x140000400,
+				error: PeError::DoesNotFit(0x1FF, DataTypes::u8),
+			},
+			ApplyError {
+				file: wide.file.clone(),
+				address: 0x140000410,
+				error: PeError::DoesNotFit(300, DataTypes::i8_arr),
+			},
+		])
+	);
+	assert_eq!(
+		compare(&image, &wide.patches[0]),
+		Err(PeError::DoesNotFit(0x1FF, DataTypes::u8))
+	);
+	assert_eq!(wide.patches[2].out_of_range(), None);
+
+	/* Two's complement hex and negative unsigned values fit */
+	let mut wrapped = Patch::new("wrapped");
+	wrapped.enabled = true;
+	wrapped.patches.push(InternalPatch {
+		address: 0x140000400,
+		data_type: DataTypes::i8_arr,
+		data_int_arr: vec![0xFF, -128],
+		..Default::default()
+	});
+	wrapped.patches.push(InternalPatch {
+		address: 0x140000410,
+		data_type: DataTypes::i32,
+		data_int: 0xFFFFFFFF,
+		..Default::default()
+	});
+	wrapped.patches.push(InternalPatch {
+		address: 0x140000420,
+		data_type: DataTypes::u16,
+		data_int: -1,
+		..Default::default()
+	});
+	let (_, changes) = image.apply(&[wrapped.clone()]).unwrap();
+	assert_eq!(changes[0].patched, vec![0xFF, 0x80]);
+	assert_eq!(changes[1].patched, vec![0xFF; 4]);
+	assert_eq!(changes[2].patched, vec![0xFF; 2]);
+	assert!(compare(&image, &wrapped.patches[0]).is_ok());
+	assert_eq!(
+		PeError::DoesNotFit(300, DataTypes::i8_arr).to_string(),
+		"300 (0x12C) does not fit in i8_arr"
+	);
+}
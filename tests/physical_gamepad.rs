@@ -0,0 +1,41 @@
+#![cfg(feature = "gui")]
+
+use launcher_egui::{
+	gamepad::GamepadState,
+	physical_gamepad::{sdl_button, stick_events},
+	Buttons,
+};
+
+#[test]
+fn controller_buttons_get_their_sdl_names() {
+	assert_eq!(sdl_button(gilrs::Button::South), Some(Buttons::SDL_A));
+	assert_eq!(sdl_button(gilrs::Button::North), Some(Buttons::SDL_Y));
+	assert_eq!(
+		sdl_button(gilrs::Button::LeftTrigger2),
+		Some(Buttons::SDL_LTRIGGER)
+	);
+	assert_eq!(
+		sdl_button(gilrs::Button::RightThumb),
+		Some(Buttons::SDL_RSTICK_PRESS)
+	);
+	assert_eq!(sdl_button(gilrs::Button::C), None);
+
+	/* Pushing a stick past the threshold and back */
+	let mut state = GamepadState::default();
+	assert_eq!(
+		state.update(&stick_events(gilrs::Axis::LeftStickY, 0.9)),
+		vec![Buttons::SDL_LSTICK_UP]
+	);
+	assert_eq!(
+		state.update(&stick_events(gilrs::Axis::LeftStickY, 0.7)),
+		vec![]
+	);
+	assert_eq!(
+		state.update(&stick_events(gilrs::Axis::LeftStickY, -0.6)),
+		vec![Buttons::SDL_LSTICK_DOWN]
+	);
+	assert_eq!(state.held, vec![Buttons::SDL_LSTICK_DOWN]);
+	state.update(&stick_events(gilrs::Axis::LeftStickY, 0.1));
+	assert_eq!(state.held, vec![]);
+	assert_eq!(stick_events(gilrs::Axis::LeftZ, 1.0), vec![]);
+}
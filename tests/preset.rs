@@ -0,0 +1,128 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	actions::ACTIONS,
+	preset::{self, Preset, PresetChange},
+	problems::ProblemKind,
+	AllConfig, Buttons,
+};
+
+#[test]
+fn export_and_import() {
+	let (_, dir) = fixture("basic", "preset_export");
+	let (config, _) = AllConfig::load(&dir);
+	assert!(preset::presets(&dir).is_empty());
+
+	let hori = Preset::export("Hori", &config.keyconfig);
+	hori.save(&dir).unwrap();
+	assert_eq!(preset::presets(&dir), vec!["Hori"]);
+	let loaded = Preset::load(&dir, "Hori").unwrap();
+	assert_eq!(loaded, hori);
+	assert!(loaded.changes(&config.keyconfig).is_empty());
+
+	/* Exporting again keeps comments added by hand */
+	let path = dir.join(preset::preset_file("Hori"));
+	let text = std::fs::read_to_string(&path).unwrap();
+	std::fs::write(&path, format!("# For the Hori controller\n{}", text)).unwrap();
+	hori.save(&dir).unwrap();
+	assert!(std::fs::read_to_string(&path)
+		.unwrap()
+		.starts_with("# For the Hori controller\n"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn preview_and_apply() {
+	let (_, dir) = fixture("basic", "preset_apply");
+	let (mut config, _) = AllConfig::load(&dir);
+	let preset = Preset::parse(
+		"partial",
+		"partial.toml",
+		"START = [\"SDL_START\"]\nCROSS = [\"S\", \"K\", \"SDL_A\"]\nCAMERA_MOVE_SLOW = []\n",
+	)
+	.unwrap();
+	assert_eq!(
+		preset.changes(&config.keyconfig),
+		vec![
+			PresetChange {
+				action: "START".to_string(),
+				from: vec![Buttons::ENTER, Buttons::SDL_START],
+				to: vec![Buttons::SDL_START],
+			},
+			PresetChange {
+				action: "CAMERA_MOVE_SLOW".to_string(),
+				from: vec![Buttons::TAB],
+				to: vec![],
+			},
+		]
+	);
+
+	/* Actions the preset leaves out keep their bindings */
+	let triangle = config.keyconfig.get("TRIANGLE").cloned();
+	preset.apply(&mut config.keyconfig);
+	assert!(preset.changes(&config.keyconfig).is_empty());
+	assert_eq!(config.keyconfig.get("TRIANGLE").cloned(), triangle);
+	assert_eq!(config.keyconfig.get("CAMERA_MOVE_SLOW"), Some(&vec![]));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn unknown_actions_and_buttons_are_reported() {
+	let problems = Preset::parse(
+		"broken",
+		"broken.toml",
+		"START = \"ENTER\"\nTRIANGLE = [\"W\", \"F13\", 3]\nTRAINGLE = [\"SPACE\"]\n",
+	)
+	.unwrap_err();
+	let found: Vec<(&str, &ProblemKind)> = problems
+		.iter()
+		.map(|problem| (problem.key.as_str(), &problem.kind))
+		.collect();
+	assert_eq!(
+		found,
+		vec![
+			(
+				"START",
+				&ProblemKind::WrongType {
+					expected: "array",
+					found: "string",
+				}
+			),
+			(
+				"TRIANGLE[1]",
+				&ProblemKind::InvalidValue("\"F13\"".to_string())
+			),
+			(
+				"TRIANGLE[2]",
+				&ProblemKind::WrongType {
+					expected: "string",
+					found: "integer",
+				}
+			),
+			(
+				"TRAINGLE",
+				&ProblemKind::InvalidValue("\"TRAINGLE\"".to_string())
+			),
+		]
+	);
+	assert!(Preset::parse("broken", "broken.toml", "START = [").is_err());
+}
+
+#[test]
+fn bundled_presets_use_known_actions() {
+	let bundled = preset::bundled();
+	assert_eq!(bundled[0].name, "Default keyboard");
+	for preset in &bundled {
+		for (action, vec) in &preset.bindings {
+			assert!(
+				ACTIONS.iter().any(|known| known.key == action),
+				"{}",
+				action
+			);
+			assert!(!vec.is_empty());
+		}
+	}
+	/* The keyboard preset sets every action */
+	assert_eq!(bundled[0].bindings.len(), ACTIONS.len());
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	problems::ProblemKind,
+	profile::{self, Profile},
+	AllConfig, Buttons,
+};
+
+#[test]
+fn save_load_and_list() {
+	let (_, dir) = fixture("basic", "profile_save");
+	let (config, _) = AllConfig::load(&dir);
+	assert!(profile::profiles(&dir).is_empty());
+
+	let tournament = Profile::snapshot("tournament", &config);
+	tournament.save(&dir).unwrap();
+	let mut practice = Profile::snapshot("practice", &config);
+	practice.config.fps = 30;
+	practice.patches[0].1 = false;
+	practice.save(&dir).unwrap();
+
+	assert_eq!(profile::profiles(&dir), vec!["practice", "tournament"]);
+	assert_eq!(Profile::load(&dir, "tournament").unwrap(), tournament);
+	assert_eq!(Profile::load(&dir, "practice").unwrap(), practice);
+
+	/* Saving over a profile keeps comments added by hand */
+	let path = dir.join(profile::profile_file("practice"));
+	let text = std::fs::read_to_string(&path).unwrap();
+	std::fs::write(&path, format!("# Weekly practice\n{}", text)).unwrap();
+	practice.config.fps = 120;
+	practice.save(&dir).unwrap();
+	let text = std::fs::read_to_string(&path).unwrap();
+	assert!(text.starts_with("# Weekly practice\n"));
+	assert_eq!(Profile::load(&dir, "practice").unwrap(), practice);
+
+	profile::delete_profile(&dir, "tournament").unwrap();
+	assert_eq!(profile::profiles(&dir), vec!["practice"]);
+	assert!(Profile::snapshot("../escape", &config).save(&dir).is_err());
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn apply_keeps_formatting() {
+	let (_, dir) = fixture("basic", "profile_apply");
+	let (mut config, _) = AllConfig::load(&dir);
+	let mut streaming = Profile::snapshot("streaming", &config);
+	streaming.config.fps = 144;
+	streaming.keyconfig.set("START", vec![Buttons::SPACE]);
+	streaming.patches[0].1 = false;
+	streaming.save(&dir).unwrap();
+
+	Profile::load(&dir, "streaming").unwrap().apply(&mut config);
+	config.save(&dir).unwrap();
+
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(Profile::snapshot("streaming", &reloaded), streaming);
+	let config_str = std::fs::read_to_string(dir.join("config.toml")).unwrap();
+	assert!(config_str.contains("# Framerate\nfps = 144\n"));
+	assert!(config_str.contains("fullscreen = false # comment"));
+	let patch_str = std::fs::read_to_string(dir.join("patches/nocredits.toml")).unwrap();
+	assert!(patch_str.contains("address = 0x140000400"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn missing_keys_get_their_default() {
+	let (_, dir) = fixture("basic", "profile_missing");
+	let (config, _) = AllConfig::load(&dir);
+	let old = Profile::snapshot("old", &config);
+	old.save(&dir).unwrap();
+	let path = dir.join(profile::profile_file("old"));
+	let text = std::fs::read_to_string(&path).unwrap();
+	assert!(text.contains("rumbleIntensity = 50\n") && text.contains("START = "));
+	let text: String = text
+		.lines()
+		.filter(|line| !line.starts_with("rumbleIntensity") && !line.starts_with("START"))
+		.map(|line| format!("{}\n", line))
+		.collect();
+	std::fs::write(&path, text).unwrap();
+
+	let loaded = Profile::load(&dir, "old").unwrap();
+	assert_eq!(loaded.config.rumble_intensity, 100);
+	assert_eq!(
+		loaded.keyconfig.get("START"),
+		Some(&vec![Buttons::ENTER, Buttons::SDL_START])
+	);
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn broken_profiles_are_reported() {
+	let (_, dir) = fixture("basic", "profile_broken");
+	let (config, _) = AllConfig::load(&dir);
+	Profile::snapshot("broken", &config).save(&dir).unwrap();
+	let path = dir.join(profile::profile_file("broken"));
+	let text = std::fs::read_to_string(&path).unwrap().replace(
+		"\"patches/nocredits.toml\" = true",
+		"\"patches/nocredits.toml\" = 1",
+	);
+	std::fs::write(&path, text).unwrap();
+
+	let problems = Profile::load(&dir, "broken").unwrap_err();
+	assert_eq!(problems.len(), 1);
+	assert_eq!(problems[0].key, "patches.patches/nocredits.toml");
+	assert_eq!(
+		problems[0].kind,
+		ProblemKind::WrongType {
+			expected: "boolean",
+			found: "integer"
+		}
+	);
+	std::fs::remove_dir_all(dir).unwrap();
+}
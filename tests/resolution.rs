@@ -0,0 +1,53 @@
+use launcher_egui::{
+	resolution::{find_preset, RenderScale, RESOLUTION_PRESETS},
+	validation::{self, AspectRatio},
+	Config,
+};
+
+#[test]
+fn presets_are_valid_16_9() {
+	for preset in RESOLUTION_PRESETS {
+		assert!(validation::INTERNAL_RES_X.check(preset.x).is_ok());
+		assert!(validation::INTERNAL_RES_Y.check(preset.y).is_ok());
+		assert_eq!(
+			AspectRatio::of(preset.x, preset.y),
+			AspectRatio::of(16, 9),
+			"{}",
+			preset.name
+		);
+	}
+	assert_eq!(
+		find_preset(2560, 1440).map(|preset| preset.name),
+		Some("1440p")
+	);
+	assert_eq!(find_preset(2560, 1080), None);
+}
+
+#[test]
+fn render_scale() {
+	let scale = RenderScale::from_resolution(2560, 1440, 1920);
+	assert_eq!(scale.percent, 75);
+	assert_eq!(scale.size(), (1920, 1080));
+	let scale = RenderScale {
+		percent: 150,
+		..scale
+	};
+	assert_eq!(scale.size(), (3840, 2160));
+	/* Rounded to whole pixels */
+	let scale = RenderScale {
+		base_x: 1366,
+		base_y: 768,
+		percent: 33,
+	};
+	assert_eq!(scale.size(), (451, 253));
+	/* Clamped to the slider range */
+	assert_eq!(RenderScale::from_resolution(1920, 1080, 7680).percent, 200);
+	assert_eq!(RenderScale::from_resolution(1920, 1080, 100).percent, 25);
+
+	let mut config = Config::default();
+	let (x, y) = RenderScale::from_resolution(3840, 2160, 3840).size();
+	config.set_internal_res(x, y).unwrap();
+	assert_eq!((config.internal_res_x, config.internal_res_y), (3840, 2160));
+	assert!(config.set_internal_res(8000, 4500).is_err());
+	assert_eq!((config.internal_res_x, config.internal_res_y), (3840, 2160));
+}
@@ -0,0 +1,329 @@
+mod common;
+
+use common::{assert_same_files, fixture};
+use launcher_egui::{
+	actions::{group_of, ActionGroup},
+	problems::ProblemKind,
+	AllConfig, Buttons, DataTypes, InternalPatch, InternalTranslation, Patch,
+};
+
+fn assert_round_trip(name: &str) {
+	let (source, dir) = fixture(name, "round_trip");
+	let (mut config, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	config.save(&dir).unwrap();
+	assert_same_files(&source, &dir);
+
+	let (reloaded, _) = AllConfig::load(&dir);
+	assert_eq!(config, reloaded);
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn basic_round_trip() {
+	assert_round_trip("basic");
+}
+
+#[test]
+fn inline_round_trip() {
+	assert_round_trip("inline");
+}
+
+#[test]
+fn other_keys_round_trip() {
+	assert_round_trip("other");
+}
+
+#[test]
+fn load_basic() {
+	let (_, dir) = fixture("basic", "load");
+	let (config, _) = AllConfig::load(&dir);
+	assert_eq!(config.config.fps, 60);
+	assert_eq!(config.config.internal_res_x, 1920);
+	assert_eq!(config.config.internal_res_y, 1080);
+	assert_eq!(
+		config.keyconfig.get("START"),
+		Some(&vec![Buttons::ENTER, Buttons::SDL_START])
+	);
+
+	assert_eq!(config.patches.len(), 1);
+	let patch = &config.patches[0];
+	assert_eq!(patch.file, "patches/nocredits.toml");
+	assert_eq!(
+		patch.patches[0],
+		InternalPatch {
+			address: 0x140000400,
+			data_type: DataTypes::u8_arr,
+			data_int_arr: vec![0x90, 0x90],
+			table: Some(0),
+			..Default::default()
+		}
+	);
+	assert_eq!(patch.patches[2].data_string, "FREE PLAY");
+
+	assert_eq!(config.translations.len(), 1);
+	assert_eq!(config.translations[0].translations.len(), 2);
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn save_changes() {
+	let (_, dir) = fixture("basic", "save_changes");
+	let (mut config, _) = AllConfig::load(&dir);
+	config.config.fps = 120;
+	config.keyconfig.set("TRIANGLE", vec![Buttons::SDL_Y]);
+	config.patches[0].patches.remove(1);
+	config.patches[0].patches[0].data_int_arr = vec![0x90];
+	config.translations[0]
+		.translations
+		.push(InternalTranslation {
+			old: "スタート".to_string(),
+			new: "Start".to_string(),
+			..Default::default()
+		});
+	config.save(&dir).unwrap();
+
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(config, reloaded);
+
+	let config_str = std::fs::read_to_string(dir.join("config.toml")).unwrap();
+	assert!(config_str.contains("# Framerate\nfps = 120\n"));
+	assert!(config_str.contains("fullscreen = false # comment"));
+	let patch_str = std::fs::read_to_string(dir.join("patches/nocredits.toml")).unwrap();
+	assert!(patch_str.contains("address = 0x140000400"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn problems_are_reported_and_files_left_alone() {
+	let (source, dir) = fixture("problems", "problems");
+	let (mut config, problems) = AllConfig::load(&dir);
+
+	let find = |file: &str, key: &str| {
+		problems
+			.iter()
+			.find(|problem| problem.file == file && problem.key == key)
+			.unwrap_or_else(|| panic!("no problem for {} {}", file, key))
+	};
+	assert_eq!(
+		find("config.toml", "fps").kind,
+		ProblemKind::WrongType {
+			expected: "integer",
+			found: "string"
+		}
+	);
+	assert_eq!(
+		find("config.toml", "internalRes.x").kind,
+		ProblemKind::FilledIn("1920".to_string())
+	);
+	assert_eq!(
+		find("keyconfig.toml", "CROSS[1]").kind,
+		ProblemKind::InvalidValue("\"NOT_A_BUTTON\"".to_string())
+	);
+	let parse = find("patches/broken.toml", "");
+	assert!(matches!(parse.kind, ProblemKind::Parse(_)));
+	assert_eq!(parse.line_col.map(|(line, _)| line), Some(5));
+
+	/* Everything that did load is still usable */
+	assert_eq!(config.config.rumble_intensity, 50);
+	assert_eq!(config.keyconfig.get("CROSS"), Some(&vec![Buttons::S]));
+	assert_eq!(config.patches.len(), 1);
+	assert_eq!(config.patches[0].name, "Fine");
+
+	config.config.fps = 60;
+	config.keyconfig.set("CROSS", vec![]);
+	assert!(config.save(&dir).is_err());
+	assert_same_files(&source, &dir);
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn patch_entries_are_added_moved_and_removed() {
+	let (_, dir) = fixture("basic", "patch_entries");
+	let (mut config, _) = AllConfig::load(&dir);
+	let patches = &mut config.patches[0].patches;
+	patches.swap(1, 2);
+	patches.insert(1, patches[0].clone());
+	patches[1].address = 0x140000500;
+	patches[1].data_int_arr = vec![0xEB];
+	patches.push(InternalPatch {
+		address: 0x140000600,
+		data_type: DataTypes::i16_arr,
+		data_int_arr: vec![1, 2, 3],
+		..Default::default()
+	});
+	config.save(&dir).unwrap();
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(config, reloaded);
+
+	/* Shrinking drops the extra tables and array elements */
+	let patches = &mut config.patches[0].patches;
+	patches.truncate(2);
+	patches[0].data_int_arr.remove(0);
+	config.save(&dir).unwrap();
+	let (reloaded, _) = AllConfig::load(&dir);
+	assert_eq!(config, reloaded);
+	let patch_str = std::fs::read_to_string(dir.join("patches/nocredits.toml")).unwrap();
+	assert_eq!(patch_str.matches("[[patch]]").count(), 2);
+	assert!(patch_str.contains("data = [0x90] # nop nop"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn comments_follow_their_patch_entries() {
+	let (_, dir) = fixture("basic", "patch_comments");
+	let (mut config, _) = AllConfig::load(&dir);
+	let patches = &mut config.patches[0].patches;
+	let credits = patches.remove(0);
+	patches.swap(0, 1);
+	/* The copy written second gets a new table */
+	patches.insert(1, credits.clone());
+	patches.insert(0, credits);
+	patches[1].address = 0x140000500;
+	config.save(&dir).unwrap();
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(config, reloaded);
+	let patch_str = std::fs::read_to_string(dir.join("patches/nocredits.toml")).unwrap();
+	assert_eq!(
+		patch_str,
+		r#"name = "No credits"
+author = "someone"
+enabled = true
+
+# Skip the credit check
+[[patch]]
+address = 0x140000400
+data_type = "u8_arr"
+data = [0x90, 0x90] # nop nop
+
+[[patch]]
+address = 0x140000500
+data_type = "string"
+data = "FREE PLAY"
+
+[[patch]]
+address = 0x140000400
+data_type = "u8_arr"
+data = [144, 144]
+
+[[patch]]
+address = 0x140000410
+data_type = "i32"
+data = 5
+"#
+	);
+
+	/* Removing the first entry takes its comment with it */
+	config.patches[0].patches.remove(0);
+	config.save(&dir).unwrap();
+	let patch_str = std::fs::read_to_string(dir.join("patches/nocredits.toml")).unwrap();
+	assert!(!patch_str.contains("# Skip the credit check"));
+	assert!(!patch_str.contains("# nop nop"));
+	assert!(patch_str.contains("data = 5\n"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn new_patch_files_are_created() {
+	let (_, dir) = fixture("basic", "new_patch");
+	let (mut config, _) = AllConfig::load(&dir);
+	config.patches.push(Patch::new("empty"));
+	let mut patch = Patch::new("widescreen");
+	patch.author = "me".to_string();
+	patch.patches.push(InternalPatch {
+		address: 0x140000800,
+		data_type: DataTypes::i32,
+		data_int: 1280,
+		..Default::default()
+	});
+	config.patches.push(patch);
+	config.save(&dir).unwrap();
+
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(reloaded.patches.len(), 3);
+	for patch in &config.patches {
+		assert!(reloaded.patches.contains(patch));
+	}
+	let patch_str = std::fs::read_to_string(dir.join("patches/empty.toml")).unwrap();
+	assert_eq!(
+		patch_str,
+		"name = \"empty\"\nauthor = \"\"\nenabled = false\n"
+	);
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn removed_bindings_are_written_exactly() {
+	let (_, dir) = fixture("inline", "bindings");
+	let (mut config, _) = AllConfig::load(&dir);
+	config.keyconfig.get_mut("TEST").unwrap().remove(0);
+	config.keyconfig.get_mut("START").unwrap().swap(0, 1);
+	config.keyconfig.get_mut("TRIANGLE").unwrap().clear();
+	config.save(&dir).unwrap();
+
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(config, reloaded);
+	let keyconfig_str = std::fs::read_to_string(dir.join("keyconfig.toml")).unwrap();
+	assert!(keyconfig_str.contains("TEST = [ \"SDL_BACK\" ]   # both\n"));
+	assert!(keyconfig_str.contains("TRIANGLE = []\n"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn unknown_keyconfig_actions_are_kept() {
+	let (_, dir) = fixture("basic", "unknown_actions");
+	let path = dir.join("keyconfig.toml");
+	let keyconfig_str = std::fs::read_to_string(&path).unwrap();
+	std::fs::write(&path, keyconfig_str + "PHOTO_MODE = [\"P\"]\n").unwrap();
+
+	let (mut config, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(group_of("PHOTO_MODE"), ActionGroup::Other);
+	assert_eq!(
+		config
+			.keyconfig
+			.group(ActionGroup::Other)
+			.collect::<Vec<_>>(),
+		vec![("PHOTO_MODE", &vec![Buttons::P])]
+	);
+	config
+		.keyconfig
+		.set("PHOTO_MODE", vec![Buttons::P, Buttons::SDL_BACK]);
+	config.save(&dir).unwrap();
+
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(config, reloaded);
+	assert!(std::fs::read_to_string(&path)
+		.unwrap()
+		.ends_with("PHOTO_MODE = [\"P\", \"SDL_BACK\"]\n"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn negative_addresses_are_saved() {
+	let (_, dir) = fixture("basic", "negative_address");
+	let path = dir.join("patches/nocredits.toml");
+	let patch_str = std::fs::read_to_string(&path).unwrap();
+	std::fs::write(&path, patch_str.replacen("0x140000400", "-5", 1)).unwrap();
+
+	let (mut config, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(config.patches[0].patches[0].address, -5);
+	config.patches[0].name = "Renamed".to_string();
+	config.patches[0].patches[1].address = -0x10;
+	config.save(&dir).unwrap();
+
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(config, reloaded);
+	let patch_str = std::fs::read_to_string(&path).unwrap();
+	assert!(patch_str.contains("address = -5\n"));
+	assert!(patch_str.contains("address = -16\n"));
+	std::fs::remove_dir_all(dir).unwrap();
+}
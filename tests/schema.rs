@@ -0,0 +1,136 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	schema::{
+		find_key, key_path, ConfigValue, Editor, SchemaDefault, CONFIG_GROUPS, CONFIG_SCHEMA,
+		EMPTY_VALUES,
+	},
+	AllConfig, Config,
+};
+
+#[test]
+fn schema_matches_config_fields() {
+	let mut config = Config::default();
+	for key in CONFIG_SCHEMA {
+		assert!(CONFIG_GROUPS.contains(&key.group), "{}", key.key);
+		match key.default {
+			SchemaDefault::Integer(default) => {
+				assert!(config.int_mut(key.key).is_some(), "{}", key.key);
+				let rule = key.rule.unwrap();
+				assert_eq!(rule.key, key.key);
+				assert!(rule.check(default).is_ok(), "{}", key.key);
+			}
+			SchemaDefault::Bool(_) => {
+				assert!(config.bool_mut(key.key).is_some(), "{}", key.key);
+				assert_eq!(key.editor, Editor::Checkbox);
+			}
+		}
+	}
+	assert_eq!(
+		find_key("internalRes.y").unwrap().editor,
+		Editor::Resolution
+	);
+	assert_eq!(find_key("vsync"), None);
+}
+
+#[test]
+fn unknown_keys_are_editable() {
+	let (_, dir) = fixture("basic", "schema_other");
+	let path = dir.join("config.toml");
+	let text = std::fs::read_to_string(&path).unwrap();
+	let text = text.replace(
+		"rumbleIntensity = 50\n",
+		"rumbleIntensity = 50\nvsync = true # keep\nscale = 1.5\ntitle = \"Diva\"\nlayers = [1, 2]\nstarted = 1979-05-27\n\"a.b\" = 3\n",
+	) + "\n[audio]\nvolume = 80\n";
+	std::fs::write(&path, &text).unwrap();
+
+	let (mut config, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	let keys: Vec<String> = config
+		.config
+		.other
+		.iter()
+		.map(|(path, _)| key_path(path))
+		.collect();
+	assert_eq!(
+		keys,
+		[
+			"vsync",
+			"scale",
+			"title",
+			"layers",
+			"\"a.b\"",
+			"audio.volume"
+		]
+	);
+	assert_eq!(
+		config.config.other[3].1,
+		ConfigValue::Array(vec![ConfigValue::Integer(1), ConfigValue::Integer(2)])
+	);
+	assert_eq!(
+		config.config.other[4],
+		(vec!["a.b".to_string()], ConfigValue::Integer(3))
+	);
+	assert_eq!(
+		config.config.other[5],
+		(
+			vec!["audio".to_string(), "volume".to_string()],
+			ConfigValue::Integer(80)
+		)
+	);
+
+	/* Unchanged values are written back as they were */
+	config.save(&dir).unwrap();
+	assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+
+	*config.config.other_mut("vsync").unwrap() = ConfigValue::Bool(false);
+	*config.config.other_mut("audio.volume").unwrap() = ConfigValue::Integer(65);
+	*config.config.other_mut("layers").unwrap() = ConfigValue::Array(vec![ConfigValue::Integer(3)]);
+	*config.config.other_mut("\"a.b\"").unwrap() = ConfigValue::Integer(4);
+	config.save(&dir).unwrap();
+	let saved = std::fs::read_to_string(&path).unwrap();
+	assert!(saved.contains("vsync = false # keep\n"));
+	assert!(saved.contains("layers = [3]\n"));
+	assert!(saved.contains("started = 1979-05-27\n"));
+	/* Not a new a = { b = 4 } next to it */
+	assert!(saved.contains("\"a.b\" = 4\n"));
+	assert_eq!(saved.matches("b = ").count(), 0);
+	assert!(saved.ends_with("[audio]\nvolume = 65\n"));
+	let (reloaded, _) = AllConfig::load(&dir);
+	assert_eq!(reloaded, config);
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn elements_are_added_to_arrays() {
+	let (_, dir) = fixture("basic", "schema_array");
+	let path = dir.join("config.toml");
+	let text = std::fs::read_to_string(&path).unwrap().replace(
+		"rumbleIntensity = 50\n",
+		"rumbleIntensity = 50\nlayers = [1.5]\nnames = []\n",
+	);
+	std::fs::write(&path, &text).unwrap();
+
+	let (mut config, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	if let ConfigValue::Array(values) = config.config.other_mut("layers").unwrap() {
+		let element = values[0].empty_like();
+		assert_eq!(element, ConfigValue::Float(0.0));
+		values.push(element);
+	}
+	if let ConfigValue::Array(values) = config.config.other_mut("names").unwrap() {
+		let string = EMPTY_VALUES
+			.iter()
+			.find(|value| value.type_name() == "string")
+			.unwrap();
+		values.push(string.clone());
+	}
+	config.save(&dir).unwrap();
+	let saved = std::fs::read_to_string(&path).unwrap();
+	assert!(saved.contains("layers = [1.5, 0.0]\n"));
+	assert!(saved.contains("names = [\"\"]\n"));
+	let (reloaded, _) = AllConfig::load(&dir);
+	assert_eq!(reloaded, config);
+	std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	pe::{PeError, PeImage, GAME_EXE},
+	problems::ProblemKind,
+	signature::{AddressSignature, Signature, SignatureError},
+	AllConfig,
+};
+use std::path::{Path, PathBuf};
+
+fn fixture_exe_path() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("tests")
+		.join("fixtures")
+		.join("executable")
+		.join(GAME_EXE)
+}
+
+fn resolve(pattern: &str, offset: i64, image: &PeImage) -> Result<i64, SignatureError> {
+	let mut signature = AddressSignature::new(pattern, offset);
+	signature.resolve(Ok(image));
+	signature.resolved.unwrap()
+}
+
+#[test]
+fn signatures_parse_with_wildcards() {
+	let signature: Signature = "48 8b ?? 24 ? 57".parse().unwrap();
+	assert_eq!(
+		signature.0,
+		[Some(0x48), Some(0x8B), None, Some(0x24), None, Some(0x57)]
+	);
+	assert_eq!(signature.to_string(), "48 8B ?? 24 ?? 57");
+	assert_eq!(
+		signature
+			.find(&[0x57, 0x48, 0x8B, 0, 0x24, 1, 0x57])
+			.collect::<Vec<_>>(),
+		[1]
+	);
+
+	assert!("48 8G".parse::<Signature>().is_err());
+	assert!("488B".parse::<Signature>().is_err());
+	assert!("?? ??".parse::<Signature>().is_err());
+	assert!("".parse::<Signature>().is_err());
+}
+
+#[test]
+fn signatures_resolve_to_one_address() {
+	let image = PeImage::load(&fixture_exe_path()).unwrap();
+	assert_eq!(resolve("48 89 5C 24 ?? 57", 0, &image), Ok(0x140000200));
+	assert_eq!(resolve("48 89 5C 24 ?? 57", 6, &image), Ok(0x140000206));
+	assert_eq!(resolve("74 05 CC", 0, &image), Ok(0x140000400));
+	assert_eq!(resolve("43 52 45 44 49 54", -0x10, &image), Ok(0x140000410));
+	/* .data starts at the same file offset as its address */
+	assert_eq!(resolve("6C 61 75 6E 63 68", 0, &image), Ok(0x140000600));
+
+	assert_eq!(
+		resolve("74 05 74 05", 0, &image),
+		Err(SignatureError::NotFound)
+	);
+	assert!(matches!(
+		resolve("CC CC", 0, &image),
+		Err(SignatureError::Ambiguous(addresses)) if addresses.len() == 2
+	));
+	assert!(matches!(
+		resolve("74 5", 0, &image),
+		Err(SignatureError::Invalid(_))
+	));
+
+	let mut signature = AddressSignature::new("74 05", 0);
+	let missing = PeError::Io("not found".to_string());
+	signature.resolve(Err(&missing));
+	assert_eq!(
+		signature.resolved,
+		Some(Err(SignatureError::Executable(missing)))
+	);
+}
+
+#[test]
+fn patches_load_and_save_signatures() {
+	let (_, dir) = fixture("basic", "signature");
+	let image = PeImage::load(&fixture_exe_path()).unwrap();
+	let path = dir.join("patches").join("nocredits.toml");
+	let text = std::fs::read_to_string(&path).unwrap();
+	std::fs::write(
+		&path,
+		text.replace(
+			"address = 0x140000410",
+			"signature = \"43 52 45 44 ?? 54\"\noffset = -16",
+		),
+	)
+	.unwrap();
+
+	/* Left unresolved unless the executable is passed in */
+	let (all, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(all.patches[0].patches[1].target(), None);
+
+	let (mut all, problems) = AllConfig::load_with_image(&dir, Ok(&image));
+	assert_eq!(problems, vec![]);
+	let entry = &all.patches[0].patches[1];
+	assert_eq!(entry.address, 0x140000410);
+	assert_eq!(entry.target(), Some(0x140000410));
+	let signature = entry.signature.as_ref().unwrap();
+	assert_eq!(signature.pattern, "43 52 45 44 ?? 54");
+	assert_eq!(signature.offset, -0x10);
+	assert_eq!(signature.resolved, Some(Ok(0x140000410)));
+
+	/* The resolved address is not written back */
+	all.save(&dir).unwrap();
+	let saved = std::fs::read_to_string(&path).unwrap();
+	assert!(saved.contains("signature = \"43 52 45 44 ?? 54\"\noffset = -16"));
+	assert!(!saved.contains("address = 0x140000410"));
+	let (reloaded, _) = AllConfig::load_with_image(&dir, Ok(&image));
+	assert_eq!(reloaded, all);
+
+	all.patches[0].patches[1].signature = None;
+	all.save(&dir).unwrap();
+	let saved = std::fs::read_to_string(&path).unwrap();
+	assert!(saved.contains("[[patch]]\naddress = 0x140000410\ndata_type = \"i32\""));
+	assert!(!saved.contains("signature"));
+	assert!(!saved.contains("offset"));
+
+	std::fs::write(
+		&path,
+		saved.replace("address = 0x140000410", "signature = \"CC CC\""),
+	)
+	.unwrap();
+	let (all, problems) = AllConfig::load_with_image(&dir, Ok(&image));
+	assert_eq!(problems.len(), 1);
+	assert_eq!(problems[0].file, "patches/nocredits.toml");
+	assert_eq!(problems[0].key, "patch[1].signature");
+	assert!(matches!(
+		&problems[0].kind,
+		ProblemKind::Signature(message) if message.starts_with("signature matches more than once")
+	));
+	assert_eq!(all.patches[0].patches[1].target(), None);
+
+	/* A skipped entry before it doesn't change which table the problem names */
+	let text = std::fs::read_to_string(&path).unwrap();
+	std::fs::write(
+		&path,
+		text.replace("data_type = \"u8_arr\"", "data_type = \"bogus\""),
+	)
+	.unwrap();
+	let (all, problems) = AllConfig::load_with_image(&dir, Ok(&image));
+	assert_eq!(all.patches[0].patches.len(), 2);
+	let keys: Vec<&str> = problems
+		.iter()
+		.map(|problem| problem.key.as_str())
+		.collect();
+	assert_eq!(keys, ["patch[0].data_type", "patch[1].signature"]);
+}
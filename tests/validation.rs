@@ -0,0 +1,146 @@
+mod common;
+
+use common::fixture;
+use launcher_egui::{
+	problems::{has_problems, ProblemKind},
+	validation::{self, AspectRatio, Axis, ConfigError, FPS_UNLIMITED},
+	AllConfig, Config,
+};
+
+#[test]
+fn rules() {
+	assert!(validation::FPS.check(1).is_ok());
+	assert!(validation::FPS.check(FPS_UNLIMITED).is_ok());
+	assert_eq!(
+		validation::FPS.check(0),
+		Err("must be between 1 and 1000, or -1 for unlimited".to_string())
+	);
+	assert!(validation::INTERNAL_RES_X.check(-1920).is_err());
+	assert_eq!(
+		validation::RUMBLE_INTENSITY.check(101),
+		Err("must be between 0 and 100".to_string())
+	);
+
+	let config = Config {
+		fps: 0,
+		internal_res_x: 1920,
+		internal_res_y: 0,
+		rumble_intensity: 50,
+		..Default::default()
+	};
+	let keys: Vec<&str> = config.validate().iter().map(|error| error.key).collect();
+	assert_eq!(keys, vec!["fps", "internalRes.y"]);
+}
+
+#[test]
+fn aspect_ratio_lock() {
+	let ratio = AspectRatio::of(1920, 1080).unwrap();
+	assert_eq!(
+		ratio,
+		AspectRatio {
+			width: 16,
+			height: 9
+		}
+	);
+	assert_eq!(AspectRatio::of(0, 1080), None);
+	assert_eq!(ratio.other_axis(Axis::X, 2560), 1440);
+	assert_eq!(ratio.other_axis(Axis::Y, 720), 1280);
+	/* 1366x768 is not exactly 16:9 */
+	assert_eq!(ratio.other_axis(Axis::X, 1366), 768);
+
+	let mut config = Config {
+		internal_res_x: 1920,
+		internal_res_y: 1080,
+		..Default::default()
+	};
+	config.set_resolution(Axis::X, 2560, Some(ratio)).unwrap();
+	assert_eq!((config.internal_res_x, config.internal_res_y), (2560, 1440));
+	config.set_resolution(Axis::Y, 1200, None).unwrap();
+	assert_eq!((config.internal_res_x, config.internal_res_y), (2560, 1200));
+
+	config.set_resolution(Axis::X, 7680, Some(ratio)).unwrap();
+	assert_eq!((config.internal_res_x, config.internal_res_y), (7680, 4320));
+
+	/* Rejected input leaves both axes alone */
+	assert_eq!(
+		config.set_resolution(Axis::X, 100, None),
+		Err("width must be between 320 and 7680".to_string())
+	);
+	assert_eq!(
+		config.set_resolution(Axis::X, 6000, Some(AspectRatio::of(1, 1).unwrap())),
+		Err("height must be between 180 and 4320".to_string())
+	);
+	assert_eq!((config.internal_res_x, config.internal_res_y), (7680, 4320));
+}
+
+#[test]
+fn out_of_range_values_are_flagged_on_load() {
+	let (_, dir) = fixture("basic", "out_of_range");
+	let path = dir.join("config.toml");
+	let text = std::fs::read_to_string(&path)
+		.unwrap()
+		.replace("fps = 60", "fps = 0")
+		.replace("x = 1920", "x = -5");
+	std::fs::write(&path, text).unwrap();
+
+	let (mut config, problems) = AllConfig::load(&dir);
+	let found: Vec<(&str, &ProblemKind)> = problems
+		.iter()
+		.map(|problem| (problem.key.as_str(), &problem.kind))
+		.collect();
+	assert_eq!(
+		found,
+		vec![
+			(
+				"fps",
+				&ProblemKind::OutOfRange(
+					"must be between 1 and 1000, or -1 for unlimited".to_string()
+				)
+			),
+			(
+				"internalRes.x",
+				&ProblemKind::OutOfRange("must be between 320 and 7680".to_string())
+			),
+		]
+	);
+	assert_eq!(
+		problems[0].to_string(),
+		"config.toml: fps: must be between 1 and 1000, or -1 for unlimited"
+	);
+
+	/* The file can still be saved, which is how it gets fixed */
+	assert!(!has_problems(&problems, "config.toml"));
+	config.config.fps = FPS_UNLIMITED;
+	config.config.internal_res_x = 1920;
+	config.save(&dir).unwrap();
+	let (reloaded, problems) = AllConfig::load(&dir);
+	assert_eq!(problems, vec![]);
+	assert_eq!(reloaded.config.validate(), Vec::<ConfigError>::new());
+	std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn values_too_big_for_the_game_are_not_wrapped() {
+	let (_, dir) = fixture("basic", "too_big");
+	let path = dir.join("config.toml");
+	let text = std::fs::read_to_string(&path)
+		.unwrap()
+		.replace("fps = 60", "fps = 4294967295");
+	std::fs::write(&path, &text).unwrap();
+
+	let (mut config, problems) = AllConfig::load(&dir);
+	assert_eq!(problems.len(), 1);
+	assert_eq!(problems[0].key, "fps");
+	assert_eq!(
+		problems[0].kind,
+		ProblemKind::InvalidValue("4294967295, expected a 32-bit integer".to_string())
+	);
+	assert_ne!(config.config.fps, FPS_UNLIMITED);
+
+	/* Saving would write whatever it was read as, so the file is left alone */
+	assert!(has_problems(&problems, "config.toml"));
+	config.config.rumble_intensity = 50;
+	assert!(config.save(&dir).is_err());
+	assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+	std::fs::remove_dir_all(dir).unwrap();
+}